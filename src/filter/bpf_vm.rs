@@ -0,0 +1,255 @@
+use crate::filter::inter::bpf_codes::{bpf_class, bpf_mode, bpf_op, bpf_rval, bpf_size, bpf_src, BPF_A, BPF_ABS, BPF_ADD, BPF_ALU, BPF_AND, BPF_B, BPF_DIV, BPF_H, BPF_IMM, BPF_IND, BPF_JA, BPF_JEQ, BPF_JGE, BPF_JGT, BPF_JMP, BPF_JSET, BPF_K, BPF_LD, BPF_LDX, BPF_LEN, BPF_LSH, BPF_MAXINSNS, BPF_MEM, BPF_MEMWORDS, BPF_MISC, BPF_MOD, BPF_MSH, BPF_MUL, BPF_NEG, BPF_OR, BPF_RET, BPF_RSH, BPF_ST, BPF_STX, BPF_SUB, BPF_TAX, BPF_TXA, BPF_W, BPF_X, BPF_XOR, SKF_AD_OFF, SKF_AD_VLAN_TAG, SKF_AD_VLAN_TAG_PRESENT};
+use crate::utils::sock_filter::SockFilter;
+
+#[derive(Debug, Clone)]
+pub struct BpfVm {
+    program: Vec<SockFilter>
+}
+
+impl BpfVm {
+
+    pub fn new(program: &[SockFilter]) -> Result<Self, String> {
+        Self::validate(program)?;
+
+        Ok(Self {
+            program: program.to_vec()
+        })
+    }
+
+    pub fn validate(program: &[SockFilter]) -> Result<(), String> {
+        if program.is_empty() || program.len() > BPF_MAXINSNS {
+            return Err(format!("Invalid program length {}", program.len()));
+        }
+
+        for (pc, insn) in program.iter().enumerate() {
+            let code = insn.get_code();
+            let k = insn.get_k();
+
+            let valid = match code {
+                c if c == BPF_ALU | BPF_NEG => true,
+                c if bpf_class(c) == BPF_ALU => {
+                    match bpf_op(c) {
+                        BPF_ADD | BPF_SUB | BPF_MUL | BPF_OR | BPF_AND | BPF_XOR => true,
+                        BPF_DIV | BPF_MOD => {
+                            if bpf_src(c) == BPF_K && k == 0 {
+                                return Err(format!("Division by zero at instruction {}", pc));
+                            }
+                            true
+                        }
+                        BPF_LSH | BPF_RSH => {
+                            if bpf_src(c) == BPF_K && k >= 32 {
+                                return Err(format!("Shift out of range at instruction {}", pc));
+                            }
+                            true
+                        }
+                        _ => false
+                    }
+                }
+                c if c == BPF_LD | BPF_W | BPF_ABS || c == BPF_LD | BPF_H | BPF_ABS || c == BPF_LD | BPF_B | BPF_ABS => true,
+                c if c == BPF_LD | BPF_W | BPF_IND || c == BPF_LD | BPF_H | BPF_IND || c == BPF_LD | BPF_B | BPF_IND => true,
+                c if c == BPF_LD | BPF_W | BPF_LEN || c == BPF_LD | BPF_IMM => true,
+                c if c == BPF_LDX | BPF_W | BPF_LEN || c == BPF_LDX | BPF_W | BPF_IMM || c == BPF_LDX | BPF_B | BPF_MSH => true,
+                c if c == BPF_LD | BPF_MEM || c == BPF_LDX | BPF_MEM || c == BPF_ST || c == BPF_STX => {
+                    if k as usize >= BPF_MEMWORDS {
+                        return Err(format!("Invalid scratch memory slot {} at instruction {}", k, pc));
+                    }
+                    true
+                }
+                c if c == BPF_RET | BPF_K || c == BPF_RET | BPF_A => true,
+                c if c == BPF_MISC | BPF_TAX || c == BPF_MISC | BPF_TXA => true,
+                c if c == BPF_JMP | BPF_JA => {
+                    if k as usize >= program.len() - pc - 1 {
+                        return Err(format!("Jump out of range at instruction {}", pc));
+                    }
+                    true
+                }
+                c if bpf_class(c) == BPF_JMP => {
+                    match bpf_op(c) {
+                        BPF_JEQ | BPF_JGT | BPF_JGE | BPF_JSET => {
+                            if pc + insn.get_jt() as usize + 1 >= program.len() || pc + insn.get_jf() as usize + 1 >= program.len() {
+                                return Err(format!("Jump out of range at instruction {}", pc));
+                            }
+                            true
+                        }
+                        _ => false
+                    }
+                }
+                _ => false
+            };
+
+            if !valid || code > 0xff {
+                return Err(format!("Invalid instruction {:#06x} at instruction {}", code, pc));
+            }
+        }
+
+        if bpf_class(program[program.len() - 1].get_code()) != BPF_RET {
+            return Err("Program must end with a return instruction".to_string());
+        }
+
+        Self::check_load_and_stores(program)
+    }
+
+    pub fn run(&self, packet: &[u8]) -> u32 {
+        self.run_with_length(packet, packet.len() as u32)
+    }
+
+    pub fn run_with_length(&self, packet: &[u8], length: u32) -> u32 {
+        self.run_with_vlan(packet, length, None)
+    }
+
+    pub fn run_with_vlan(&self, packet: &[u8], length: u32, vlan: Option<u16>) -> u32 {
+        let mut a: u32 = 0;
+        let mut x: u32 = 0;
+        let mut mem = [0u32; BPF_MEMWORDS];
+        let mut pc = 0;
+
+        loop {
+            let insn = &self.program[pc];
+            let code = insn.get_code();
+            let k = insn.get_k();
+            pc += 1;
+
+            match bpf_class(code) {
+                BPF_RET => {
+                    return if bpf_rval(code) == BPF_A { a } else { k };
+                }
+                BPF_LD => {
+                    a = match bpf_mode(code) {
+                        BPF_ABS if k >= SKF_AD_OFF => match k - SKF_AD_OFF {
+                            SKF_AD_VLAN_TAG => vlan.unwrap_or(0) as u32,
+                            SKF_AD_VLAN_TAG_PRESENT => vlan.is_some() as u32,
+                            _ => return 0
+                        },
+                        BPF_ABS => match load(packet, k as u64, bpf_size(code)) {
+                            Some(value) => value,
+                            None => return 0
+                        },
+                        BPF_IND => match load(packet, x as u64 + k as u64, bpf_size(code)) {
+                            Some(value) => value,
+                            None => return 0
+                        },
+                        BPF_MEM => mem[k as usize],
+                        BPF_LEN => length,
+                        _ => k
+                    };
+                }
+                BPF_LDX => {
+                    x = match bpf_mode(code) {
+                        BPF_MEM => mem[k as usize],
+                        BPF_LEN => length,
+                        BPF_MSH => match packet.get(k as usize) {
+                            Some(byte) => ((byte & 0x0f) as u32) << 2,
+                            None => return 0
+                        },
+                        _ => k
+                    };
+                }
+                BPF_ST => mem[k as usize] = a,
+                BPF_STX => mem[k as usize] = x,
+                BPF_ALU => {
+                    let operand = if bpf_src(code) == BPF_X { x } else { k };
+
+                    a = match bpf_op(code) {
+                        BPF_ADD => a.wrapping_add(operand),
+                        BPF_SUB => a.wrapping_sub(operand),
+                        BPF_MUL => a.wrapping_mul(operand),
+                        BPF_DIV => {
+                            if operand == 0 {
+                                return 0;
+                            }
+                            a / operand
+                        }
+                        BPF_MOD => {
+                            if operand == 0 {
+                                return 0;
+                            }
+                            a % operand
+                        }
+                        BPF_AND => a & operand,
+                        BPF_OR => a | operand,
+                        BPF_XOR => a ^ operand,
+                        BPF_LSH => a.checked_shl(operand).unwrap_or(0),
+                        BPF_RSH => a.checked_shr(operand).unwrap_or(0),
+                        _ => a.wrapping_neg()
+                    };
+                }
+                BPF_JMP => {
+                    let operand = if bpf_src(code) == BPF_X { x } else { k };
+
+                    let taken = match bpf_op(code) {
+                        BPF_JA => {
+                            pc += k as usize;
+                            continue;
+                        }
+                        BPF_JEQ => a == operand,
+                        BPF_JGT => a > operand,
+                        BPF_JGE => a >= operand,
+                        _ => a & operand != 0
+                    };
+
+                    pc += if taken { insn.get_jt() } else { insn.get_jf() } as usize;
+                }
+                _ => {
+                    if code & BPF_TXA != 0 {
+                        a = x;
+                    } else {
+                        x = a;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn get_program(&self) -> &[SockFilter] {
+        &self.program
+    }
+
+    fn check_load_and_stores(program: &[SockFilter]) -> Result<(), String> {
+        let mut masks = vec![u16::MAX; program.len()];
+        let mut valid: u16 = 0;
+
+        for (pc, insn) in program.iter().enumerate() {
+            let code = insn.get_code();
+            let k = insn.get_k();
+            valid &= masks[pc];
+
+            match code {
+                c if c == BPF_ST || c == BPF_STX => valid |= 1 << k,
+                c if (c == BPF_LD | BPF_MEM || c == BPF_LDX | BPF_MEM) && valid & (1 << k) == 0 => {
+                    return Err(format!("Scratch memory slot {} read before write at instruction {}", k, pc));
+                }
+                c if c == BPF_JMP | BPF_JA => {
+                    masks[pc + 1 + k as usize] &= valid;
+                    valid = u16::MAX;
+                }
+                c if bpf_class(c) == BPF_JMP => {
+                    masks[pc + 1 + insn.get_jt() as usize] &= valid;
+                    masks[pc + 1 + insn.get_jf() as usize] &= valid;
+                    valid = u16::MAX;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn load(packet: &[u8], offset: u64, size: u16) -> Option<u32> {
+    let length = match size {
+        BPF_W => 4,
+        BPF_H => 2,
+        _ => 1
+    };
+
+    if offset + length > packet.len() as u64 {
+        return None;
+    }
+
+    let bytes = &packet[offset as usize..(offset + length) as usize];
+    Some(match size {
+        BPF_W => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        BPF_H => u16::from_be_bytes([bytes[0], bytes[1]]) as u32,
+        _ => bytes[0] as u32
+    })
+}
@@ -0,0 +1,725 @@
+use std::net::IpAddr;
+use crate::filter::inter::bpf_codes::{BPF_ABS, BPF_ADD, BPF_ALU, BPF_B, BPF_H, BPF_IMM, BPF_IND, BPF_JA, BPF_JEQ, BPF_JGE, BPF_JGT, BPF_JMP, BPF_JSET, BPF_K, BPF_LD, BPF_LDX, BPF_LEN, BPF_MAXINSNS, BPF_MEM, BPF_MEMWORDS, BPF_MISC, BPF_MSH, BPF_RET, BPF_ST, BPF_TAX, BPF_W, BPF_X, SKF_AD_OFF, SKF_AD_VLAN_TAG, SKF_AD_VLAN_TAG_PRESENT};
+use crate::filter::inter::directions::Directions;
+use crate::filter::inter::expression::{Expression, Value};
+use crate::filter::inter::operators::Operators;
+use crate::filter::inter::protocols::Protocols;
+use crate::filter::inter::relations::Relations;
+use crate::packet::layers::ethernet_frame::inter::ethernet_address::EthernetAddress;
+use crate::packet::layers::ethernet_frame::inter::ethernet_types::EthernetTypes;
+use crate::packet::layers::ip::inter::ip_protocols::IpProtocols;
+use crate::packet::layers::loop_frame::inter::loop_types::LoopTypes;
+use crate::utils::data_link_types::DataLinkTypes;
+use crate::utils::sock_filter::{SockFilter, DEFAULT_SNAPLEN};
+
+const IPPROTO_SCTP: u8 = 132;
+
+#[derive(Clone, Debug)]
+enum Pred {
+    True,
+    False,
+    And(Box<Pred>, Box<Pred>),
+    Or(Box<Pred>, Box<Pred>),
+    Not(Box<Pred>),
+    Cmp(Val, Relations, Val),
+    Jset(Val, u32)
+}
+
+#[derive(Clone, Debug)]
+enum Val {
+    Const(u32),
+    Len,
+    Ancillary(u32),
+    Load(Box<Val>, u32, u8, bool),
+    Binary(Operators, Box<Val>, Box<Val>)
+}
+
+#[derive(Clone, Debug)]
+enum Insn {
+    Stmt(SockFilter),
+    Jump(u16, u32, usize, usize),
+    Goto(usize),
+    Mark(usize)
+}
+
+pub struct Compiler {
+    data_link_type: DataLinkTypes,
+    link_length: u32,
+    vlan_offset: u32,
+    kernel: bool,
+    vlan_stripped: bool,
+    insns: Vec<Insn>,
+    labels: usize,
+    scratch: usize
+}
+
+impl Compiler {
+
+    pub fn new(data_link_type: DataLinkTypes) -> Result<Self, String> {
+        let link_length = match data_link_type {
+            DataLinkTypes::En10mb => 14,
+            DataLinkTypes::Sll2 => 20,
+            DataLinkTypes::Null | DataLinkTypes::Loop => 4,
+            DataLinkTypes::Raw | DataLinkTypes::Ipv4 | DataLinkTypes::Ipv6 => 0,
+            _ => return Err(format!("Filtering is not supported on {} links", data_link_type.to_string()))
+        };
+
+        Ok(Self {
+            data_link_type,
+            link_length,
+            vlan_offset: 0,
+            kernel: false,
+            vlan_stripped: false,
+            insns: Vec::new(),
+            labels: 0,
+            scratch: 0
+        })
+    }
+
+    pub fn set_kernel(&mut self, kernel: bool) {
+        self.kernel = kernel;
+    }
+
+    pub fn compile(mut self, expression: Option<&Expression>) -> Result<Vec<SockFilter>, String> {
+        let expression = match expression {
+            Some(expression) => expression,
+            None => return Ok(vec![SockFilter::new(BPF_RET | BPF_K, 0, 0, DEFAULT_SNAPLEN)])
+        };
+
+        let pred = self.lower(expression)?;
+
+        let accept = self.label();
+        let reject = self.label();
+        self.gen_pred(&pred, accept, reject)?;
+
+        self.insns.push(Insn::Mark(accept));
+        self.stmt(BPF_RET | BPF_K, DEFAULT_SNAPLEN);
+        self.insns.push(Insn::Mark(reject));
+        self.stmt(BPF_RET | BPF_K, 0);
+
+        self.assemble()
+    }
+
+    fn lower(&mut self, expression: &Expression) -> Result<Pred, String> {
+        match expression {
+            Expression::And(a, b) => {
+                let a = self.lower(a)?;
+                Ok(and(a, self.lower(b)?))
+            }
+            Expression::Or(a, b) => {
+                let a = self.lower(a)?;
+                Ok(or(a, self.lower(b)?))
+            }
+            Expression::Not(a) => Ok(not(self.lower(a)?)),
+            Expression::Host(protocol, direction, address) => {
+                match address {
+                    IpAddr::V4(address) => {
+                        let address = u32::from(*address);
+                        let host = |offset: u32| Pred::Cmp(self.net(offset, 4), Relations::Eq, Val::Const(address));
+
+                        let ip = and(self.ether_type(EthernetTypes::Ipv4.get_code()), direction_of(*direction, host(12), host(16)));
+                        let arp = and(self.ether_type(EthernetTypes::Arp.get_code()), direction_of(*direction, host(14), host(24)));
+                        let rarp = and(self.ether_type(EthernetTypes::Rarp.get_code()), direction_of(*direction, host(14), host(24)));
+
+                        match protocol {
+                            Some(Protocols::Ip) => Ok(ip),
+                            Some(Protocols::Arp) => Ok(arp),
+                            Some(Protocols::Rarp) => Ok(rarp),
+                            _ => Ok(or(or(ip, arp), rarp))
+                        }
+                    }
+                    IpAddr::V6(address) => {
+                        let address = u128::from(*address);
+                        Ok(and(self.ether_type(EthernetTypes::Ipv6.get_code()), direction_of(*direction, self.net6(8, address, 128), self.net6(24, address, 128))))
+                    }
+                }
+            }
+            Expression::EtherHost(direction, address) => {
+                self.require_ethernet("ether host")?;
+                Ok(direction_of(*direction, self.ether_address(6, address), self.ether_address(0, address)))
+            }
+            Expression::Net(protocol, direction, address, prefix) => {
+                match address {
+                    IpAddr::V4(address) => {
+                        let address = u32::from(*address);
+                        let net = |offset: u32| self.net4(offset, address, *prefix);
+
+                        let ip = and(self.ether_type(EthernetTypes::Ipv4.get_code()), direction_of(*direction, net(12), net(16)));
+                        let arp = and(self.ether_type(EthernetTypes::Arp.get_code()), direction_of(*direction, net(14), net(24)));
+                        let rarp = and(self.ether_type(EthernetTypes::Rarp.get_code()), direction_of(*direction, net(14), net(24)));
+
+                        match protocol {
+                            Some(Protocols::Ip) => Ok(ip),
+                            Some(Protocols::Arp) => Ok(arp),
+                            Some(Protocols::Rarp) => Ok(rarp),
+                            _ => Ok(or(or(ip, arp), rarp))
+                        }
+                    }
+                    IpAddr::V6(address) => {
+                        let address = u128::from(*address);
+                        Ok(and(self.ether_type(EthernetTypes::Ipv6.get_code()), direction_of(*direction, self.net6(8, address, *prefix), self.net6(24, address, *prefix))))
+                    }
+                }
+            }
+            Expression::Port(protocol, direction, start, end) => {
+                let protocols = match protocol {
+                    Some(Protocols::Tcp) => vec![IpProtocols::Tcp.get_code()],
+                    Some(Protocols::Udp) => vec![IpProtocols::Udp.get_code()],
+                    Some(Protocols::Sctp) => vec![IPPROTO_SCTP],
+                    _ => vec![IpProtocols::Tcp.get_code(), IpProtocols::Udp.get_code(), IPPROTO_SCTP]
+                };
+
+                let range = |value: Val| {
+                    if start == end {
+                        Pred::Cmp(value, Relations::Eq, Val::Const(*start as u32))
+
+                    } else {
+                        and(Pred::Cmp(value.clone(), Relations::Ge, Val::Const(*start as u32)), Pred::Cmp(value, Relations::Le, Val::Const(*end as u32)))
+                    }
+                };
+
+                let v4 = and(
+                    self.ether_type(EthernetTypes::Ipv4.get_code()),
+                    and(
+                        and(self.next_header(9, &protocols), self.not_fragment()),
+                        direction_of(*direction, range(self.transport(0, 2)), range(self.transport(2, 2)))
+                    )
+                );
+
+                let v6 = and(
+                    self.ether_type(EthernetTypes::Ipv6.get_code()),
+                    and(
+                        self.next_header(6, &protocols),
+                        direction_of(*direction, range(self.net(40, 2)), range(self.net(42, 2)))
+                    )
+                );
+
+                Ok(or(v4, v6))
+            }
+            Expression::Protocol(protocol) => self.protocol(*protocol),
+            Expression::IpProtocol(protocol, code) => Ok(self.ip_protocol(*protocol, *code)),
+            Expression::EtherProtocol(code) => Ok(self.ether_type(*code)),
+            Expression::Broadcast(protocol) => {
+                match protocol {
+                    Protocols::Ether => {
+                        self.require_ethernet("broadcast")?;
+                        Ok(self.ether_address(0, &EthernetAddress::new(0xff, 0xff, 0xff, 0xff, 0xff, 0xff)))
+                    }
+                    _ => Err(format!("'{} broadcast' is not supported", protocol.get_name()))
+                }
+            }
+            Expression::Multicast(protocol) => {
+                match protocol {
+                    Protocols::Ether => {
+                        self.require_ethernet("multicast")?;
+                        Ok(Pred::Jset(self.link(0, 1), 0x01))
+                    }
+                    Protocols::Ip => Ok(and(self.ether_type(EthernetTypes::Ipv4.get_code()), Pred::Cmp(self.net(16, 1), Relations::Ge, Val::Const(224)))),
+                    Protocols::Ip6 => Ok(and(self.ether_type(EthernetTypes::Ipv6.get_code()), Pred::Cmp(self.net(24, 1), Relations::Eq, Val::Const(0xff)))),
+                    _ => Err(format!("'{} multicast' is not supported", protocol.get_name()))
+                }
+            }
+            Expression::Vlan(id) => {
+                self.require_ethernet("vlan")?;
+
+                //LINUX STRIPS THE OUTER TAG BEFORE THE SOCKET FILTER RUNS, IT IS ONLY REACHABLE THROUGH ANCILLARY LOADS
+                if self.kernel && !self.vlan_stripped {
+                    self.vlan_stripped = true;
+
+                    let mut pred = Pred::Cmp(Val::Ancillary(SKF_AD_VLAN_TAG_PRESENT), Relations::Eq, Val::Const(1));
+                    if let Some(id) = id {
+                        let tci = Val::Binary(Operators::And, Box::new(Val::Ancillary(SKF_AD_VLAN_TAG)), Box::new(Val::Const(0x0fff)));
+                        pred = and(pred, Pred::Cmp(tci, Relations::Eq, Val::Const(*id as u32)));
+                    }
+
+                    return Ok(pred);
+                }
+
+                let tpid = self.link(12 + self.vlan_offset, 2);
+                let mut pred = or(
+                    Pred::Cmp(tpid.clone(), Relations::Eq, Val::Const(EthernetTypes::Vlan.get_code() as u32)),
+                    or(Pred::Cmp(tpid.clone(), Relations::Eq, Val::Const(EthernetTypes::QinQ.get_code() as u32)), Pred::Cmp(tpid, Relations::Eq, Val::Const(0x9100)))
+                );
+
+                if let Some(id) = id {
+                    let tci = Val::Binary(Operators::And, Box::new(self.link(14 + self.vlan_offset, 2)), Box::new(Val::Const(0x0fff)));
+                    pred = and(pred, Pred::Cmp(tci, Relations::Eq, Val::Const(*id as u32)));
+                }
+
+                self.vlan_offset += 4;
+                Ok(pred)
+            }
+            Expression::Relation(left, relation, right) => {
+                let mut guards = Vec::new();
+                let left = self.lower_value(left, &mut guards)?;
+                let right = self.lower_value(right, &mut guards)?;
+
+                let mut pred = Pred::True;
+                for protocol in guards {
+                    pred = and(pred, self.guard(protocol)?);
+                }
+
+                Ok(and(pred, Pred::Cmp(left, *relation, right)))
+            }
+        }
+    }
+
+    fn lower_value(&self, value: &Value, guards: &mut Vec<Protocols>) -> Result<Val, String> {
+        match value {
+            Value::Const(k) => Ok(Val::Const(*k)),
+            Value::Len => Ok(Val::Len),
+            Value::Binary(operator, left, right) => {
+                let left = self.lower_value(left, guards)?;
+                let right = self.lower_value(right, guards)?;
+
+                if let (Operators::Div | Operators::Mod, Val::Const(0)) = (operator, &right) {
+                    return Err("Division by zero in filter expression".to_string());
+                }
+
+                if let (Val::Const(a), Val::Const(b)) = (&left, &right) {
+                    return Ok(Val::Const(match operator {
+                        Operators::Add => a.wrapping_add(*b),
+                        Operators::Sub => a.wrapping_sub(*b),
+                        Operators::Mul => a.wrapping_mul(*b),
+                        Operators::Div => a / b,
+                        Operators::Mod => a % b,
+                        Operators::And => a & b,
+                        Operators::Or => a | b,
+                        Operators::Xor => a ^ b,
+                        Operators::Lsh => a.checked_shl(*b).unwrap_or(0),
+                        Operators::Rsh => a.checked_shr(*b).unwrap_or(0)
+                    }));
+                }
+
+                Ok(Val::Binary(*operator, Box::new(left), Box::new(right)))
+            }
+            Value::Load(protocol, offset, size) => {
+                let offset = Box::new(self.lower_value(offset, guards)?);
+
+                if !guards.contains(protocol) {
+                    guards.push(*protocol);
+                }
+
+                let nl = self.network_offset();
+                Ok(match protocol {
+                    Protocols::Ether => {
+                        self.require_ethernet("ether[]")?;
+                        Val::Load(offset, 0, *size, false)
+                    }
+                    Protocols::Ip | Protocols::Ip6 | Protocols::Arp | Protocols::Rarp => Val::Load(offset, nl, *size, false),
+                    Protocols::Tcp | Protocols::Udp | Protocols::Sctp | Protocols::Icmp => Val::Load(offset, nl, *size, true),
+                    Protocols::Icmp6 => Val::Load(offset, nl + 40, *size, false)
+                })
+            }
+        }
+    }
+
+    fn guard(&self, protocol: Protocols) -> Result<Pred, String> {
+        let code = match protocol {
+            Protocols::Ether => return Ok(Pred::True),
+            Protocols::Tcp => IpProtocols::Tcp.get_code(),
+            Protocols::Udp => IpProtocols::Udp.get_code(),
+            Protocols::Sctp => IPPROTO_SCTP,
+            Protocols::Icmp => IpProtocols::Icmp.get_code(),
+            _ => return self.protocol(protocol)
+        };
+
+        Ok(and(self.ip_protocol(Some(Protocols::Ip), code), self.not_fragment()))
+    }
+
+    fn protocol(&self, protocol: Protocols) -> Result<Pred, String> {
+        match protocol {
+            Protocols::Ether => Err("'ether' must be followed by a qualifier".to_string()),
+            Protocols::Ip => Ok(self.ether_type(EthernetTypes::Ipv4.get_code())),
+            Protocols::Ip6 => Ok(self.ether_type(EthernetTypes::Ipv6.get_code())),
+            Protocols::Arp => Ok(self.ether_type(EthernetTypes::Arp.get_code())),
+            Protocols::Rarp => Ok(self.ether_type(EthernetTypes::Rarp.get_code())),
+            Protocols::Tcp => Ok(self.ip_protocol(None, IpProtocols::Tcp.get_code())),
+            Protocols::Udp => Ok(self.ip_protocol(None, IpProtocols::Udp.get_code())),
+            Protocols::Sctp => Ok(self.ip_protocol(None, IPPROTO_SCTP)),
+            Protocols::Icmp => Ok(self.ip_protocol(Some(Protocols::Ip), IpProtocols::Icmp.get_code())),
+            Protocols::Icmp6 => Ok(self.ip_protocol(Some(Protocols::Ip6), IpProtocols::Icmpv6.get_code()))
+        }
+    }
+
+    fn ip_protocol(&self, version: Option<Protocols>, code: u8) -> Pred {
+        let v4 = and(self.ether_type(EthernetTypes::Ipv4.get_code()), self.next_header(9, &[code]));
+        let v6 = and(self.ether_type(EthernetTypes::Ipv6.get_code()), self.next_header(6, &[code]));
+
+        match version {
+            Some(Protocols::Ip) => v4,
+            Some(Protocols::Ip6) => v6,
+            _ => or(v4, v6)
+        }
+    }
+
+    fn ether_type(&self, code: u16) -> Pred {
+        let _type = EthernetTypes::from_code(code).ok();
+
+        match self.data_link_type {
+            DataLinkTypes::En10mb => Pred::Cmp(self.link(12 + self.vlan_offset, 2), Relations::Eq, Val::Const(code as u32)),
+            DataLinkTypes::Sll2 => Pred::Cmp(self.link(0, 2), Relations::Eq, Val::Const(code as u32)),
+            DataLinkTypes::Raw => {
+                let version = match _type {
+                    Some(EthernetTypes::Ipv4) => 0x40,
+                    Some(EthernetTypes::Ipv6) => 0x60,
+                    _ => return Pred::False
+                };
+
+                Pred::Cmp(Val::Binary(Operators::And, Box::new(self.link(0, 1)), Box::new(Val::Const(0xf0))), Relations::Eq, Val::Const(version))
+            }
+            DataLinkTypes::Ipv4 => if _type == Some(EthernetTypes::Ipv4) { Pred::True } else { Pred::False },
+            DataLinkTypes::Ipv6 => if _type == Some(EthernetTypes::Ipv6) { Pred::True } else { Pred::False },
+            _ => {
+                let families = match _type {
+                    Some(EthernetTypes::Ipv4) => vec![LoopTypes::Ipv4],
+                    Some(EthernetTypes::Ipv6) => vec![LoopTypes::Ipv6, LoopTypes::Ipv6e2, LoopTypes::Ipv6e3],
+                    _ => return Pred::False
+                };
+
+                let mut pred = Pred::False;
+                for family in families {
+                    let family = match self.data_link_type {
+                        DataLinkTypes::Loop => family.get_code(),
+                        _ => u32::from_be_bytes(family.get_code().to_ne_bytes())
+                    };
+
+                    pred = or(pred, Pred::Cmp(self.link(0, 4), Relations::Eq, Val::Const(family)));
+                }
+
+                pred
+            }
+        }
+    }
+
+    fn next_header(&self, offset: u32, protocols: &[u8]) -> Pred {
+        let mut pred = Pred::False;
+        for protocol in protocols {
+            pred = or(pred, Pred::Cmp(self.net(offset, 1), Relations::Eq, Val::Const(*protocol as u32)));
+        }
+
+        pred
+    }
+
+    fn not_fragment(&self) -> Pred {
+        not(Pred::Jset(self.net(6, 2), 0x1fff))
+    }
+
+    fn ether_address(&self, offset: u32, address: &EthernetAddress) -> Pred {
+        let bytes = address.to_bytes();
+
+        and(
+            Pred::Cmp(self.link(offset + 2, 4), Relations::Eq, Val::Const(u32::from_be_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]))),
+            Pred::Cmp(self.link(offset, 2), Relations::Eq, Val::Const(u16::from_be_bytes([bytes[0], bytes[1]]) as u32))
+        )
+    }
+
+    fn net4(&self, offset: u32, address: u32, prefix: u8) -> Pred {
+        match prefix {
+            0 => Pred::True,
+            32 => Pred::Cmp(self.net(offset, 4), Relations::Eq, Val::Const(address)),
+            _ => {
+                let mask = u32::MAX << (32 - prefix as u32);
+                Pred::Cmp(Val::Binary(Operators::And, Box::new(self.net(offset, 4)), Box::new(Val::Const(mask))), Relations::Eq, Val::Const(address & mask))
+            }
+        }
+    }
+
+    fn net6(&self, offset: u32, address: u128, prefix: u8) -> Pred {
+        let mut pred = Pred::True;
+
+        for i in 0..4 {
+            let bits = (prefix as i32 - i * 32).clamp(0, 32) as u8;
+            let word = (address >> (96 - i * 32)) as u32;
+            pred = and(pred, self.net4(offset + i as u32 * 4, word, bits));
+        }
+
+        pred
+    }
+
+    fn require_ethernet(&self, primitive: &str) -> Result<(), String> {
+        if self.data_link_type != DataLinkTypes::En10mb {
+            return Err(format!("'{}' is not supported on {} links", primitive, self.data_link_type.to_string()));
+        }
+
+        Ok(())
+    }
+
+    fn network_offset(&self) -> u32 {
+        self.link_length + self.vlan_offset
+    }
+
+    fn link(&self, offset: u32, size: u8) -> Val {
+        Val::Load(Box::new(Val::Const(offset)), 0, size, false)
+    }
+
+    fn net(&self, offset: u32, size: u8) -> Val {
+        Val::Load(Box::new(Val::Const(offset)), self.network_offset(), size, false)
+    }
+
+    fn transport(&self, offset: u32, size: u8) -> Val {
+        Val::Load(Box::new(Val::Const(offset)), self.network_offset(), size, true)
+    }
+
+    fn gen_pred(&mut self, pred: &Pred, t: usize, f: usize) -> Result<(), String> {
+        match pred {
+            Pred::True => self.insns.push(Insn::Goto(t)),
+            Pred::False => self.insns.push(Insn::Goto(f)),
+            Pred::And(a, b) => {
+                let next = self.label();
+                self.gen_pred(a, next, f)?;
+                self.insns.push(Insn::Mark(next));
+                self.gen_pred(b, t, f)?;
+            }
+            Pred::Or(a, b) => {
+                let next = self.label();
+                self.gen_pred(a, t, next)?;
+                self.insns.push(Insn::Mark(next));
+                self.gen_pred(b, t, f)?;
+            }
+            Pred::Not(a) => self.gen_pred(a, f, t)?,
+            Pred::Jset(value, mask) => {
+                self.gen_val(value)?;
+                self.insns.push(Insn::Jump(BPF_JMP | BPF_JSET | BPF_K, *mask, t, f));
+            }
+            Pred::Cmp(left, relation, right) => {
+                let (code, t, f) = match relation {
+                    Relations::Eq => (BPF_JEQ, t, f),
+                    Relations::Ne => (BPF_JEQ, f, t),
+                    Relations::Gt => (BPF_JGT, t, f),
+                    Relations::Ge => (BPF_JGE, t, f),
+                    Relations::Lt => (BPF_JGE, f, t),
+                    Relations::Le => (BPF_JGT, f, t)
+                };
+
+                match right {
+                    Val::Const(k) => {
+                        if let (BPF_JEQ, 0, Val::Binary(Operators::And, value, mask)) = (code, k, left) {
+                            if let Val::Const(mask) = **mask {
+                                self.gen_val(value)?;
+                                self.insns.push(Insn::Jump(BPF_JMP | BPF_JSET | BPF_K, mask, f, t));
+                                return Ok(());
+                            }
+                        }
+
+                        self.gen_val(left)?;
+                        self.insns.push(Insn::Jump(BPF_JMP | code | BPF_K, *k, t, f));
+                    }
+                    _ => {
+                        let slot = self.alloc()?;
+                        self.gen_val(right)?;
+                        self.stmt(BPF_ST, slot as u32);
+                        self.gen_val(left)?;
+                        self.stmt(BPF_LDX | BPF_MEM, slot as u32);
+                        self.insns.push(Insn::Jump(BPF_JMP | code | BPF_X, 0, t, f));
+                        self.scratch -= 1;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn gen_val(&mut self, value: &Val) -> Result<(), String> {
+        match value {
+            Val::Const(k) => self.stmt(BPF_LD | BPF_IMM, *k),
+            Val::Len => self.stmt(BPF_LD | BPF_W | BPF_LEN, 0),
+            Val::Ancillary(k) => self.stmt(BPF_LD | BPF_W | BPF_ABS, SKF_AD_OFF + k),
+            Val::Load(offset, base, size, transport) => {
+                let size = match size {
+                    1 => BPF_B,
+                    2 => BPF_H,
+                    _ => BPF_W
+                };
+
+                match **offset {
+                    Val::Const(k) => {
+                        if *transport {
+                            self.stmt(BPF_LDX | BPF_B | BPF_MSH, *base);
+                            self.stmt(BPF_LD | size | BPF_IND, base + k);
+
+                        } else {
+                            self.stmt(BPF_LD | size | BPF_ABS, base + k);
+                        }
+                    }
+                    _ => {
+                        self.gen_val(offset)?;
+
+                        if *transport {
+                            self.stmt(BPF_LDX | BPF_B | BPF_MSH, *base);
+                            self.stmt(BPF_ALU | BPF_ADD | BPF_X, 0);
+                        }
+
+                        self.stmt(BPF_MISC | BPF_TAX, 0);
+                        self.stmt(BPF_LD | size | BPF_IND, *base);
+                    }
+                }
+            }
+            Val::Binary(operator, left, right) => {
+                match **right {
+                    Val::Const(k) => {
+                        self.gen_val(left)?;
+                        self.stmt(BPF_ALU | operator.get_code() | BPF_K, k);
+                    }
+                    _ => {
+                        let slot = self.alloc()?;
+                        self.gen_val(right)?;
+                        self.stmt(BPF_ST, slot as u32);
+                        self.gen_val(left)?;
+                        self.stmt(BPF_LDX | BPF_MEM, slot as u32);
+                        self.stmt(BPF_ALU | operator.get_code() | BPF_X, 0);
+                        self.scratch -= 1;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn assemble(mut self) -> Result<Vec<SockFilter>, String> {
+        let mut i = 0;
+        while i < self.insns.len() {
+            if let Insn::Goto(target) = self.insns[i] {
+                let mut j = i + 1;
+                let mut redundant = false;
+
+                while let Some(Insn::Mark(label)) = self.insns.get(j) {
+                    if *label == target {
+                        redundant = true;
+                    }
+                    j += 1;
+                }
+
+                if redundant {
+                    self.insns.remove(i);
+                    continue;
+                }
+            }
+
+            i += 1;
+        }
+
+        let positions = loop {
+            let mut positions = vec![0; self.labels];
+            let mut pos = 0;
+            for insn in &self.insns {
+                match insn {
+                    Insn::Mark(label) => positions[*label] = pos,
+                    _ => pos += 1
+                }
+            }
+
+            let mut pos = 0;
+            let mut expand = None;
+            for (i, insn) in self.insns.iter().enumerate() {
+                match insn {
+                    Insn::Mark(_) => continue,
+                    Insn::Jump(_, _, t, f) => {
+                        if positions[*t] - pos - 1 > u8::MAX as usize {
+                            expand = Some((i, true));
+                            break;
+                        }
+
+                        if positions[*f] - pos - 1 > u8::MAX as usize {
+                            expand = Some((i, false));
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+
+                pos += 1;
+            }
+
+            match expand {
+                Some((i, taken)) => {
+                    let label = self.label();
+                    let target = match &mut self.insns[i] {
+                        Insn::Jump(_, _, t, f) => {
+                            let target = if taken { t } else { f };
+                            std::mem::replace(target, label)
+                        }
+                        _ => unreachable!()
+                    };
+
+                    self.insns.insert(i + 1, Insn::Goto(target));
+                    self.insns.insert(i + 1, Insn::Mark(label));
+                }
+                None => break positions
+            }
+        };
+
+        let mut program = Vec::new();
+        for insn in &self.insns {
+            let pos = program.len();
+            match insn {
+                Insn::Stmt(stmt) => program.push(*stmt),
+                Insn::Jump(code, k, t, f) => program.push(SockFilter::new(*code, (positions[*t] - pos - 1) as u8, (positions[*f] - pos - 1) as u8, *k)),
+                Insn::Goto(target) => program.push(SockFilter::new(BPF_JMP | BPF_JA, 0, 0, (positions[*target] - pos - 1) as u32)),
+                Insn::Mark(_) => {}
+            }
+        }
+
+        if program.len() > BPF_MAXINSNS {
+            return Err(format!("Filter expression is too complex ({} instructions)", program.len()));
+        }
+
+        Ok(program)
+    }
+
+    fn stmt(&mut self, code: u16, k: u32) {
+        self.insns.push(Insn::Stmt(SockFilter::new(code, 0, 0, k)));
+    }
+
+    fn label(&mut self) -> usize {
+        self.labels += 1;
+        self.labels - 1
+    }
+
+    fn alloc(&mut self) -> Result<usize, String> {
+        if self.scratch >= BPF_MEMWORDS {
+            return Err("Filter expression is too complex (out of scratch memory)".to_string());
+        }
+
+        self.scratch += 1;
+        Ok(self.scratch - 1)
+    }
+}
+
+fn and(a: Pred, b: Pred) -> Pred {
+    match (a, b) {
+        (Pred::False, _) | (_, Pred::False) => Pred::False,
+        (Pred::True, b) => b,
+        (a, Pred::True) => a,
+        (a, b) => Pred::And(Box::new(a), Box::new(b))
+    }
+}
+
+fn or(a: Pred, b: Pred) -> Pred {
+    match (a, b) {
+        (Pred::True, _) | (_, Pred::True) => Pred::True,
+        (Pred::False, b) => b,
+        (a, Pred::False) => a,
+        (a, b) => Pred::Or(Box::new(a), Box::new(b))
+    }
+}
+
+fn not(a: Pred) -> Pred {
+    match a {
+        Pred::True => Pred::False,
+        Pred::False => Pred::True,
+        Pred::Not(a) => *a,
+        a => Pred::Not(Box::new(a))
+    }
+}
+
+fn direction_of(direction: Directions, src: Pred, dst: Pred) -> Pred {
+    match direction {
+        Directions::Src => src,
+        Directions::Dst => dst,
+        Directions::SrcOrDst => or(src, dst),
+        Directions::SrcAndDst => and(src, dst)
+    }
+}
@@ -0,0 +1,65 @@
+use crate::filter::bpf_vm::BpfVm;
+use crate::filter::compiler::Compiler;
+use crate::filter::lexer::tokenize;
+use crate::filter::parser::Parser;
+use crate::utils::data_link_types::DataLinkTypes;
+use crate::utils::sock_filter::SockFilter;
+
+#[derive(Debug, Clone)]
+pub struct Filter {
+    expression: String,
+    data_link_type: DataLinkTypes,
+    kernel: bool,
+    vm: BpfVm
+}
+
+impl Filter {
+
+    pub fn compile(expression: &str, data_link_type: DataLinkTypes) -> Result<Self, String> {
+        Self::compile_with(expression, data_link_type, false)
+    }
+
+    pub fn compile_for_kernel(expression: &str, data_link_type: DataLinkTypes) -> Result<Self, String> {
+        Self::compile_with(expression, data_link_type, true)
+    }
+
+    fn compile_with(expression: &str, data_link_type: DataLinkTypes, kernel: bool) -> Result<Self, String> {
+        let tokens = tokenize(expression)?;
+        let expr = Parser::new(tokens).parse()?;
+
+        let mut compiler = Compiler::new(data_link_type)?;
+        compiler.set_kernel(kernel);
+        let program = compiler.compile(expr.as_ref())?;
+
+        Ok(Self {
+            expression: expression.to_string(),
+            data_link_type,
+            kernel,
+            vm: BpfVm::new(&program)?
+        })
+    }
+
+    pub fn get_expression(&self) -> String {
+        self.expression.clone()
+    }
+
+    pub fn get_data_link_type(&self) -> DataLinkTypes {
+        self.data_link_type
+    }
+
+    pub fn is_kernel(&self) -> bool {
+        self.kernel
+    }
+
+    pub fn get_program(&self) -> &[SockFilter] {
+        self.vm.get_program()
+    }
+
+    pub fn matches(&self, data: &[u8]) -> bool {
+        self.vm.run(data) > 0
+    }
+
+    pub fn matches_with_vlan(&self, data: &[u8], vlan: Option<u16>) -> bool {
+        self.vm.run_with_vlan(data, data.len() as u32, vlan) > 0
+    }
+}
@@ -0,0 +1,19 @@
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Directions {
+    Src,
+    Dst,
+    SrcOrDst,
+    SrcAndDst
+}
+
+impl Directions {
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Src => "src",
+            Self::Dst => "dst",
+            Self::SrcOrDst => "src or dst",
+            Self::SrcAndDst => "src and dst"
+        }.to_string()
+    }
+}
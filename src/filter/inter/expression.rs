@@ -0,0 +1,32 @@
+use std::net::IpAddr;
+use crate::filter::inter::directions::Directions;
+use crate::filter::inter::operators::Operators;
+use crate::filter::inter::protocols::Protocols;
+use crate::filter::inter::relations::Relations;
+use crate::packet::layers::ethernet_frame::inter::ethernet_address::EthernetAddress;
+
+#[derive(Clone, Debug)]
+pub enum Expression {
+    And(Box<Expression>, Box<Expression>),
+    Or(Box<Expression>, Box<Expression>),
+    Not(Box<Expression>),
+    Host(Option<Protocols>, Directions, IpAddr),
+    EtherHost(Directions, EthernetAddress),
+    Net(Option<Protocols>, Directions, IpAddr, u8),
+    Port(Option<Protocols>, Directions, u16, u16),
+    Protocol(Protocols),
+    IpProtocol(Option<Protocols>, u8),
+    EtherProtocol(u16),
+    Broadcast(Protocols),
+    Multicast(Protocols),
+    Vlan(Option<u16>),
+    Relation(Value, Relations, Value)
+}
+
+#[derive(Clone, Debug)]
+pub enum Value {
+    Const(u32),
+    Len,
+    Load(Protocols, Box<Value>, u8),
+    Binary(Operators, Box<Value>, Box<Value>)
+}
@@ -0,0 +1,6 @@
+pub mod bpf_codes;
+pub mod expression;
+pub mod protocols;
+pub mod directions;
+pub mod relations;
+pub mod operators;
@@ -0,0 +1,58 @@
+use crate::filter::inter::bpf_codes::{BPF_ADD, BPF_AND, BPF_DIV, BPF_LSH, BPF_MOD, BPF_MUL, BPF_OR, BPF_RSH, BPF_SUB, BPF_XOR};
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Operators {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    And,
+    Or,
+    Xor,
+    Lsh,
+    Rsh
+}
+
+impl Operators {
+
+    pub fn from_symbol(symbol: &str) -> Result<Self, String> {
+        for c in [Self::Add, Self::Sub, Self::Mul, Self::Div, Self::Mod, Self::And, Self::Or, Self::Xor, Self::Lsh, Self::Rsh] {
+            if c.to_string() == symbol {
+                return Ok(c);
+            }
+        }
+
+        Err(format!("Couldn't find for symbol: {}", symbol))
+    }
+
+    pub fn get_code(&self) -> u16 {
+        match self {
+            Self::Add => BPF_ADD,
+            Self::Sub => BPF_SUB,
+            Self::Mul => BPF_MUL,
+            Self::Div => BPF_DIV,
+            Self::Mod => BPF_MOD,
+            Self::And => BPF_AND,
+            Self::Or => BPF_OR,
+            Self::Xor => BPF_XOR,
+            Self::Lsh => BPF_LSH,
+            Self::Rsh => BPF_RSH
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Mod => "%",
+            Self::And => "&",
+            Self::Or => "|",
+            Self::Xor => "^",
+            Self::Lsh => "<<",
+            Self::Rsh => ">>"
+        }.to_string()
+    }
+}
@@ -0,0 +1,60 @@
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Protocols {
+    Ether,
+    Ip,
+    Ip6,
+    Arp,
+    Rarp,
+    Tcp,
+    Udp,
+    Sctp,
+    Icmp,
+    Icmp6
+}
+
+impl Protocols {
+
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        if name == "link" {
+            return Ok(Self::Ether);
+        }
+
+        for c in [Self::Ether, Self::Ip, Self::Ip6, Self::Arp, Self::Rarp, Self::Tcp, Self::Udp, Self::Sctp, Self::Icmp, Self::Icmp6] {
+            if c.get_name() == name {
+                return Ok(c);
+            }
+        }
+
+        Err(format!("Couldn't find for name: {}", name))
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Ether => "ether",
+            Self::Ip => "ip",
+            Self::Ip6 => "ip6",
+            Self::Arp => "arp",
+            Self::Rarp => "rarp",
+            Self::Tcp => "tcp",
+            Self::Udp => "udp",
+            Self::Sctp => "sctp",
+            Self::Icmp => "icmp",
+            Self::Icmp6 => "icmp6"
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Ether => "Ethernet",
+            Self::Ip => "IPv4",
+            Self::Ip6 => "IPv6",
+            Self::Arp => "ARP",
+            Self::Rarp => "RARP",
+            Self::Tcp => "TCP",
+            Self::Udp => "UDP",
+            Self::Sctp => "SCTP",
+            Self::Icmp => "ICMP",
+            Self::Icmp6 => "ICMPv6"
+        }.to_string()
+    }
+}
@@ -0,0 +1,35 @@
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Relations {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le
+}
+
+impl Relations {
+
+    pub fn from_symbol(symbol: &str) -> Result<Self, String> {
+        match symbol {
+            "=" | "==" => Ok(Self::Eq),
+            "!=" => Ok(Self::Ne),
+            ">" => Ok(Self::Gt),
+            ">=" => Ok(Self::Ge),
+            "<" => Ok(Self::Lt),
+            "<=" => Ok(Self::Le),
+            _ => Err(format!("Couldn't find for symbol: {}", symbol))
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Lt => "<",
+            Self::Le => "<="
+        }.to_string()
+    }
+}
@@ -0,0 +1,102 @@
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Token {
+    Ident(String),
+    Number(u32),
+    Address(String),
+    Symbol(&'static str)
+}
+
+impl Token {
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Ident(s) | Self::Address(s) => s.clone(),
+            Self::Number(n) => n.to_string(),
+            Self::Symbol(s) => s.to_string()
+        }
+    }
+}
+
+const SYMBOLS: [&str; 24] = [
+    "&&", "||", "==", "!=", "<=", ">=", "<<", ">>",
+    "(", ")", "[", "]", ":", "+", "-", "*", "/", "%", "&", "|", "^", "=", "<", ">"
+];
+
+pub fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut depth = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() || c == '\\' {
+            i += 1;
+            continue;
+        }
+
+        if c.is_ascii_alphanumeric() || c == '_' || (c == ':' && depth == 0 && chars.get(i + 1) == Some(&':')) {
+            let start = i;
+            let alpha = c.is_ascii_alphabetic();
+
+            while i < chars.len() {
+                let c = chars[i];
+                let hyphen = c == '-' && alpha && chars.get(i + 1).is_some_and(|c| c.is_ascii_alphanumeric());
+
+                if !(c.is_ascii_alphanumeric() || c == '_' || c == '.' || (c == ':' && depth == 0) || hyphen) {
+                    break;
+                }
+
+                i += 1;
+            }
+
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(classify(word)?);
+            continue;
+        }
+
+        if c == '!' && chars.get(i + 1) != Some(&'=') {
+            tokens.push(Token::Ident("not".to_string()));
+            i += 1;
+            continue;
+        }
+
+        let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+        match SYMBOLS.iter().find(|s| rest.starts_with(**s)) {
+            Some(symbol) => {
+                match *symbol {
+                    "[" => depth += 1,
+                    "]" => depth -= 1,
+                    _ => {}
+                }
+
+                tokens.push(Token::Symbol(symbol));
+                i += symbol.len();
+            }
+            None => return Err(format!("Illegal character '{}' in filter expression", c))
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn classify(word: String) -> Result<Token, String> {
+    if word.contains(':') || word.contains('.') {
+        return Ok(Token::Address(word));
+    }
+
+    if let Some(hex) = word.strip_prefix("0x").or_else(|| word.strip_prefix("0X")) {
+        return u32::from_str_radix(hex, 16)
+            .map(Token::Number)
+            .map_err(|_| format!("Invalid number '{}'", word));
+    }
+
+    if word.chars().all(|c| c.is_ascii_digit()) {
+        return word.parse::<u32>()
+            .map(Token::Number)
+            .map_err(|_| format!("Invalid number '{}'", word));
+    }
+
+    Ok(Token::Ident(word))
+}
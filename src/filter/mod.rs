@@ -0,0 +1,6 @@
+pub mod inter;
+pub mod filter;
+pub mod bpf_vm;
+mod lexer;
+mod parser;
+mod compiler;
@@ -0,0 +1,632 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use crate::filter::inter::directions::Directions;
+use crate::filter::inter::expression::{Expression, Value};
+use crate::filter::inter::operators::Operators;
+use crate::filter::inter::protocols::Protocols;
+use crate::filter::inter::relations::Relations;
+use crate::filter::lexer::Token;
+use crate::packet::layers::ethernet_frame::inter::ethernet_address::EthernetAddress;
+use crate::packet::layers::ethernet_frame::inter::ethernet_types::EthernetTypes;
+use crate::packet::layers::ip::inter::ip_protocols::IpProtocols;
+
+const PRECEDENCE: [&[&str]; 5] = [
+    &["|", "^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"]
+];
+
+const SERVICES: [(&str, u16); 22] = [
+    ("ftp-data", 20),
+    ("ftp", 21),
+    ("ssh", 22),
+    ("telnet", 23),
+    ("smtp", 25),
+    ("domain", 53),
+    ("bootps", 67),
+    ("bootpc", 68),
+    ("tftp", 69),
+    ("http", 80),
+    ("www", 80),
+    ("pop3", 110),
+    ("sunrpc", 111),
+    ("ntp", 123),
+    ("netbios-ns", 137),
+    ("imap", 143),
+    ("snmp", 161),
+    ("bgp", 179),
+    ("ldap", 389),
+    ("https", 443),
+    ("syslog", 514),
+    ("mdns", 5353)
+];
+
+const CONSTANTS: [(&str, u32); 31] = [
+    ("tcpflags", 13),
+    ("tcp-fin", 0x01),
+    ("tcp-syn", 0x02),
+    ("tcp-rst", 0x04),
+    ("tcp-push", 0x08),
+    ("tcp-ack", 0x10),
+    ("tcp-urg", 0x20),
+    ("tcp-ece", 0x40),
+    ("tcp-cwr", 0x80),
+    ("icmptype", 0),
+    ("icmpcode", 1),
+    ("icmp-echoreply", 0),
+    ("icmp-unreach", 3),
+    ("icmp-sourcequench", 4),
+    ("icmp-redirect", 5),
+    ("icmp-echo", 8),
+    ("icmp-routeradvert", 9),
+    ("icmp-routersolicit", 10),
+    ("icmp-timxceed", 11),
+    ("icmp-paramprob", 12),
+    ("icmp-tstamp", 13),
+    ("icmp-tstampreply", 14),
+    ("icmp-maskreq", 17),
+    ("icmp-maskreply", 18),
+    ("icmp6type", 0),
+    ("icmp6code", 1),
+    ("icmp6-echo", 128),
+    ("icmp6-echoreply", 129),
+    ("icmp6-routersolicit", 133),
+    ("icmp6-neighborsolicit", 135),
+    ("icmp6-neighboradvert", 136)
+];
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Kinds {
+    Host,
+    Net,
+    Port,
+    PortRange
+}
+
+pub struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    last: Option<(Option<Protocols>, Directions, Kinds)>
+}
+
+impl Parser {
+
+    pub fn new(tokens: Vec<Token>) -> Self {
+        Self {
+            tokens,
+            pos: 0,
+            last: None
+        }
+    }
+
+    pub fn parse(&mut self) -> Result<Option<Expression>, String> {
+        if self.tokens.is_empty() {
+            return Ok(None);
+        }
+
+        let expression = self.parse_or()?;
+
+        match self.peek() {
+            Some(token) => Err(format!("Syntax error near '{}'", token.to_string())),
+            None => Ok(Some(expression))
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_and()?;
+
+        while self.is_ident("or") || self.is_symbol("||") {
+            self.pos += 1;
+            expression = Expression::Or(Box::new(expression), Box::new(self.parse_and()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> Result<Expression, String> {
+        let mut expression = self.parse_not()?;
+
+        while self.is_ident("and") || self.is_symbol("&&") {
+            self.pos += 1;
+            expression = Expression::And(Box::new(expression), Box::new(self.parse_not()?));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_not(&mut self) -> Result<Expression, String> {
+        if self.is_ident("not") {
+            self.pos += 1;
+            return Ok(Expression::Not(Box::new(self.parse_not()?)));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expression, String> {
+        if self.starts_value() {
+            let pos = self.pos;
+            match self.parse_relation() {
+                Ok(relation) => return Ok(relation),
+                Err(e) => {
+                    self.pos = pos;
+
+                    if self.is_symbol("(") {
+                        self.pos += 1;
+                        let expression = self.parse_or()?;
+                        self.expect_symbol(")")?;
+                        return Ok(expression);
+                    }
+
+                    if let Some(Token::Ident(_)) = self.peek() {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        self.parse_primitive()
+    }
+
+    fn parse_relation(&mut self) -> Result<Expression, String> {
+        let left = self.parse_value(0)?;
+
+        let relation = match self.next() {
+            Some(Token::Symbol(symbol)) => Relations::from_symbol(symbol)
+                .map_err(|_| format!("Syntax error near '{}'", symbol))?,
+            Some(token) => return Err(format!("Syntax error near '{}'", token.to_string())),
+            None => return Err("Unexpected end of filter expression".to_string())
+        };
+
+        let right = self.parse_value(0)?;
+
+        Ok(Expression::Relation(left, relation, right))
+    }
+
+    fn parse_value(&mut self, level: usize) -> Result<Value, String> {
+        if level == PRECEDENCE.len() {
+            return self.parse_unary();
+        }
+
+        let mut value = self.parse_value(level + 1)?;
+
+        while let Some(Token::Symbol(symbol)) = self.peek() {
+            if !PRECEDENCE[level].contains(symbol) {
+                break;
+            }
+
+            let operator = Operators::from_symbol(symbol)?;
+            self.pos += 1;
+            value = Value::Binary(operator, Box::new(value), Box::new(self.parse_value(level + 1)?));
+        }
+
+        Ok(value)
+    }
+
+    fn parse_unary(&mut self) -> Result<Value, String> {
+        if self.is_symbol("-") {
+            self.pos += 1;
+            return Ok(Value::Binary(Operators::Sub, Box::new(Value::Const(0)), Box::new(self.parse_unary()?)));
+        }
+
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Value::Const(n)),
+            Some(Token::Symbol("(")) => {
+                let value = self.parse_value(0)?;
+                self.expect_symbol(")")?;
+                Ok(value)
+            }
+            Some(Token::Ident(word)) => {
+                if word == "len" {
+                    return Ok(Value::Len);
+                }
+
+                if let Some((_, value)) = CONSTANTS.iter().find(|(name, _)| *name == word) {
+                    return Ok(Value::Const(*value));
+                }
+
+                let protocol = Protocols::from_name(&word)
+                    .map_err(|_| format!("Syntax error near '{}'", word))?;
+
+                self.expect_symbol("[")?;
+                let offset = self.parse_value(0)?;
+
+                let size = if self.is_symbol(":") {
+                    self.pos += 1;
+                    match self.next() {
+                        Some(Token::Number(n)) if n == 1 || n == 2 || n == 4 => n as u8,
+                        _ => return Err("Data size must be 1, 2 or 4".to_string())
+                    }
+                } else {
+                    1
+                };
+
+                self.expect_symbol("]")?;
+                Ok(Value::Load(protocol, Box::new(offset), size))
+            }
+            Some(token) => Err(format!("Syntax error near '{}'", token.to_string())),
+            None => Err("Unexpected end of filter expression".to_string())
+        }
+    }
+
+    fn parse_primitive(&mut self) -> Result<Expression, String> {
+        let word = match self.peek() {
+            Some(Token::Ident(word)) => word.clone(),
+            Some(_) => return self.parse_inherited(),
+            None => return Err("Unexpected end of filter expression".to_string())
+        };
+
+        match word.as_str() {
+            "vlan" => {
+                self.pos += 1;
+                return match self.peek() {
+                    Some(Token::Number(n)) => {
+                        let id = *n;
+                        if id > 4095 {
+                            return Err(format!("VLAN id {} out of range", id));
+                        }
+
+                        self.pos += 1;
+                        Ok(Expression::Vlan(Some(id as u16)))
+                    }
+                    _ => Ok(Expression::Vlan(None))
+                };
+            }
+            "less" | "greater" => {
+                self.pos += 1;
+                let length = self.parse_number()?;
+                let relation = if word == "less" { Relations::Le } else { Relations::Ge };
+                return Ok(Expression::Relation(Value::Len, relation, Value::Const(length)));
+            }
+            "broadcast" => {
+                self.pos += 1;
+                return Ok(Expression::Broadcast(Protocols::Ether));
+            }
+            "multicast" => {
+                self.pos += 1;
+                return Ok(Expression::Multicast(Protocols::Ether));
+            }
+            "proto" => {
+                self.pos += 1;
+                return Ok(Expression::IpProtocol(None, self.parse_ip_protocol()?));
+            }
+            _ => {}
+        }
+
+        let protocol = Protocols::from_name(&word).ok();
+        if protocol.is_some() {
+            self.pos += 1;
+        }
+
+        if let Some(protocol) = protocol {
+            if self.is_ident("proto") {
+                self.pos += 1;
+                return match protocol {
+                    Protocols::Ether => Ok(Expression::EtherProtocol(self.parse_ether_protocol()?)),
+                    Protocols::Ip | Protocols::Ip6 => Ok(Expression::IpProtocol(Some(protocol), self.parse_ip_protocol()?)),
+                    _ => Err(format!("'{} proto' is not supported", protocol.get_name()))
+                };
+            }
+
+            if self.is_ident("broadcast") {
+                self.pos += 1;
+                return Ok(Expression::Broadcast(protocol));
+            }
+
+            if self.is_ident("multicast") {
+                self.pos += 1;
+                return Ok(Expression::Multicast(protocol));
+            }
+        }
+
+        let direction = self.parse_direction();
+        let kind = self.parse_kind();
+
+        if direction.is_none() && kind.is_none() {
+            return match protocol {
+                Some(protocol) => Ok(Expression::Protocol(protocol)),
+                None => self.parse_inherited()
+            };
+        }
+
+        let direction = direction.unwrap_or(Directions::SrcOrDst);
+        let kind = kind.unwrap_or(Kinds::Host);
+        self.last = Some((protocol, direction, kind));
+
+        self.parse_id(protocol, direction, kind)
+    }
+
+    fn parse_inherited(&mut self) -> Result<Expression, String> {
+        let (protocol, direction, kind) = self.last.unwrap_or((None, Directions::SrcOrDst, Kinds::Host));
+        self.parse_id(protocol, direction, kind)
+    }
+
+    fn parse_direction(&mut self) -> Option<Directions> {
+        let direction = if self.is_ident("src") {
+            Directions::Src
+        } else if self.is_ident("dst") {
+            Directions::Dst
+        } else {
+            return None;
+        };
+
+        self.pos += 1;
+
+        let other = if direction == Directions::Src { "dst" } else { "src" };
+        if matches!(self.peek_at(1), Some(Token::Ident(word)) if word == other) {
+            if self.is_ident("or") {
+                self.pos += 2;
+                return Some(Directions::SrcOrDst);
+            }
+
+            if self.is_ident("and") {
+                self.pos += 2;
+                return Some(Directions::SrcAndDst);
+            }
+        }
+
+        Some(direction)
+    }
+
+    fn parse_kind(&mut self) -> Option<Kinds> {
+        let kind = match self.peek() {
+            Some(Token::Ident(word)) => match word.as_str() {
+                "host" => Kinds::Host,
+                "net" => Kinds::Net,
+                "port" => Kinds::Port,
+                "portrange" => Kinds::PortRange,
+                _ => return None
+            },
+            _ => return None
+        };
+
+        self.pos += 1;
+        Some(kind)
+    }
+
+    fn parse_id(&mut self, protocol: Option<Protocols>, direction: Directions, kind: Kinds) -> Result<Expression, String> {
+        match kind {
+            Kinds::Host => {
+                let id = match self.next() {
+                    Some(Token::Address(id)) => id,
+                    Some(Token::Ident(id)) => return Err(format!("Unable to resolve host name '{}'", id)),
+                    Some(token) => return Err(format!("Invalid host '{}'", token.to_string())),
+                    None => return Err("Unexpected end of filter expression".to_string())
+                };
+
+                if protocol.is_none() || protocol == Some(Protocols::Ether) {
+                    if let Some(address) = parse_ethernet_address(&id) {
+                        return Ok(Expression::EtherHost(direction, address));
+                    }
+                }
+
+                match id.parse::<IpAddr>() {
+                    Ok(address) => {
+                        match (protocol, address) {
+                            (None, _) |
+                            (Some(Protocols::Ip), IpAddr::V4(_)) |
+                            (Some(Protocols::Arp), IpAddr::V4(_)) |
+                            (Some(Protocols::Rarp), IpAddr::V4(_)) |
+                            (Some(Protocols::Ip6), IpAddr::V6(_)) => Ok(Expression::Host(protocol, direction, address)),
+                            (Some(protocol), _) => Err(format!("Invalid address '{}' for '{} host'", id, protocol.get_name()))
+                        }
+                    }
+                    Err(_) => Err(format!("Unable to resolve host name '{}'", id))
+                }
+            }
+            Kinds::Net => {
+                let (address, mut prefix) = match self.next() {
+                    Some(Token::Number(n)) if n <= 255 => (IpAddr::V4(Ipv4Addr::new(n as u8, 0, 0, 0)), 8),
+                    Some(Token::Address(id)) => parse_network(&id)?,
+                    Some(token) => return Err(format!("Invalid network '{}'", token.to_string())),
+                    None => return Err("Unexpected end of filter expression".to_string())
+                };
+
+                if self.is_symbol("/") {
+                    self.pos += 1;
+                    prefix = self.parse_number()?;
+
+                } else if self.is_ident("mask") {
+                    self.pos += 1;
+                    prefix = match self.next() {
+                        Some(Token::Address(mask)) => parse_mask(&mask)?,
+                        _ => return Err("Invalid network mask".to_string())
+                    };
+                }
+
+                let max = if address.is_ipv4() { 32 } else { 128 };
+                if prefix > max {
+                    return Err(format!("Invalid prefix length {}", prefix));
+                }
+
+                let bits = match address {
+                    IpAddr::V4(address) => u32::from(address) as u128,
+                    IpAddr::V6(address) => u128::from(address)
+                };
+
+                if prefix < max && bits & ((1u128 << (max - prefix)) - 1) != 0 {
+                    return Err("Non-network bits set in network address".to_string());
+                }
+
+                match (protocol, address) {
+                    (None, _) |
+                    (Some(Protocols::Ip), IpAddr::V4(_)) |
+                    (Some(Protocols::Arp), IpAddr::V4(_)) |
+                    (Some(Protocols::Rarp), IpAddr::V4(_)) |
+                    (Some(Protocols::Ip6), IpAddr::V6(_)) => Ok(Expression::Net(protocol, direction, address, prefix as u8)),
+                    (Some(protocol), _) => Err(format!("Invalid network for '{} net'", protocol.get_name()))
+                }
+            }
+            Kinds::Port | Kinds::PortRange => {
+                match protocol {
+                    None | Some(Protocols::Tcp) | Some(Protocols::Udp) | Some(Protocols::Sctp) => {}
+                    Some(protocol) => return Err(format!("Illegal qualifier '{}' for port", protocol.get_name()))
+                }
+
+                let start = self.parse_port()?;
+
+                let end = if kind == Kinds::PortRange {
+                    self.expect_symbol("-")?;
+                    self.parse_port()?
+
+                } else {
+                    start
+                };
+
+                Ok(Expression::Port(protocol, direction, start.min(end), start.max(end)))
+            }
+        }
+    }
+
+    fn parse_port(&mut self) -> Result<u16, String> {
+        match self.next() {
+            Some(Token::Number(n)) if n <= u16::MAX as u32 => Ok(n as u16),
+            Some(Token::Ident(name)) => SERVICES.iter()
+                .find(|(service, _)| *service == name)
+                .map(|(_, port)| *port)
+                .ok_or(format!("Unknown port '{}'", name)),
+            Some(token) => Err(format!("Invalid port '{}'", token.to_string())),
+            None => Err("Unexpected end of filter expression".to_string())
+        }
+    }
+
+    fn parse_ip_protocol(&mut self) -> Result<u8, String> {
+        match self.next() {
+            Some(Token::Number(n)) if n <= u8::MAX as u32 => Ok(n as u8),
+            Some(Token::Ident(name)) => {
+                match name.as_str() {
+                    "icmp" => Ok(IpProtocols::Icmp.get_code()),
+                    "igmp" => Ok(IpProtocols::Igmp.get_code()),
+                    "tcp" => Ok(IpProtocols::Tcp.get_code()),
+                    "udp" => Ok(IpProtocols::Udp.get_code()),
+                    "gre" => Ok(IpProtocols::Gre.get_code()),
+                    "esp" => Ok(50),
+                    "ah" => Ok(51),
+                    "icmp6" => Ok(IpProtocols::Icmpv6.get_code()),
+                    "ospf" => Ok(IpProtocols::Ospf.get_code()),
+                    "sctp" => Ok(132),
+                    _ => Err(format!("Unknown ip proto '{}'", name))
+                }
+            }
+            Some(token) => Err(format!("Invalid ip proto '{}'", token.to_string())),
+            None => Err("Unexpected end of filter expression".to_string())
+        }
+    }
+
+    fn parse_ether_protocol(&mut self) -> Result<u16, String> {
+        match self.next() {
+            Some(Token::Number(n)) if n <= u16::MAX as u32 => Ok(n as u16),
+            Some(Token::Ident(name)) => {
+                match name.as_str() {
+                    "ip" => Ok(EthernetTypes::Ipv4.get_code()),
+                    "ip6" => Ok(EthernetTypes::Ipv6.get_code()),
+                    "arp" => Ok(EthernetTypes::Arp.get_code()),
+                    "rarp" => Ok(0x8035),
+                    _ => Err(format!("Unknown ether proto '{}'", name))
+                }
+            }
+            Some(token) => Err(format!("Invalid ether proto '{}'", token.to_string())),
+            None => Err("Unexpected end of filter expression".to_string())
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<u32, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(n),
+            Some(token) => Err(format!("Expected a number near '{}'", token.to_string())),
+            None => Err("Unexpected end of filter expression".to_string())
+        }
+    }
+
+    fn starts_value(&self) -> bool {
+        match self.peek() {
+            Some(Token::Number(_)) => true,
+            Some(Token::Symbol(symbol)) => *symbol == "(" || *symbol == "-",
+            Some(Token::Ident(word)) => {
+                word == "len" ||
+                    CONSTANTS.iter().any(|(name, _)| name == word) ||
+                    (Protocols::from_name(word).is_ok() && self.peek_at(1) == Some(&Token::Symbol("[")))
+            }
+            _ => false
+        }
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Symbol(s)) if s == symbol => Ok(()),
+            Some(token) => Err(format!("Expected '{}' near '{}'", symbol, token.to_string())),
+            None => Err(format!("Expected '{}' at end of filter expression", symbol))
+        }
+    }
+
+    fn is_ident(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(word)) if word == name)
+    }
+
+    fn is_symbol(&self, symbol: &str) -> bool {
+        matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+}
+
+fn parse_ethernet_address(id: &str) -> Option<EthernetAddress> {
+    let parts: Vec<&str> = id.split(':').collect();
+    if parts.len() != 6 {
+        return None;
+    }
+
+    let mut address = [0u8; 6];
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() || part.len() > 2 {
+            return None;
+        }
+
+        address[i] = u8::from_str_radix(part, 16).ok()?;
+    }
+
+    Some(EthernetAddress::new(address[0], address[1], address[2], address[3], address[4], address[5]))
+}
+
+fn parse_network(id: &str) -> Result<(IpAddr, u32), String> {
+    if id.contains(':') {
+        return id.parse::<Ipv6Addr>()
+            .map(|address| (IpAddr::V6(address), 128))
+            .map_err(|_| format!("Invalid network '{}'", id));
+    }
+
+    let parts: Vec<&str> = id.split('.').collect();
+    if parts.len() > 4 {
+        return Err(format!("Invalid network '{}'", id));
+    }
+
+    let mut octets = [0u8; 4];
+    for (i, part) in parts.iter().enumerate() {
+        octets[i] = part.parse::<u8>().map_err(|_| format!("Invalid network '{}'", id))?;
+    }
+
+    Ok((IpAddr::V4(Ipv4Addr::from(octets)), parts.len() as u32 * 8))
+}
+
+fn parse_mask(mask: &str) -> Result<u32, String> {
+    let mask = u32::from(mask.parse::<Ipv4Addr>().map_err(|_| format!("Invalid network mask '{}'", mask))?);
+
+    if mask.leading_ones() + mask.trailing_zeros() != 32 {
+        return Err("Network mask must be contiguous".to_string());
+    }
+
+    Ok(mask.leading_ones())
+}
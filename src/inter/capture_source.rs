@@ -0,0 +1,22 @@
+use std::io;
+use crate::packet::packet::Packet;
+use crate::utils::capture_stats::CaptureStats;
+use crate::utils::data_link_types::DataLinkTypes;
+use crate::utils::sock_filter::SockFilter;
+
+pub trait CaptureSource {
+
+    fn open(&mut self) -> io::Result<()>;
+
+    fn recv(&self) -> io::Result<Packet>;
+
+    fn try_recv(&self) -> io::Result<Packet>;
+
+    fn send(&self, packet: Packet) -> io::Result<usize>;
+
+    fn stats(&self) -> io::Result<CaptureStats>;
+
+    fn set_filter(&self, filter: &[SockFilter]) -> io::Result<()>;
+
+    fn get_data_link_type(&self) -> DataLinkTypes;
+}
@@ -0,0 +1 @@
+pub mod capture_source;
@@ -1,3 +1,5 @@
+pub mod filter;
+pub mod inter;
 pub mod packet;
 pub mod pcap;
 pub mod utils;
@@ -45,10 +47,44 @@ Ethernet frames (L2)	Write an NDIS filter driver	Full kernel driver	😬 (very h
 
 #[cfg(test)]
 mod tests {
-    use crate::capture::Capture;
+    use std::io::ErrorKind;
+    use std::time::{Duration, Instant};
     use crate::devices::Device;
+    use crate::filter::bpf_vm::BpfVm;
+    use crate::filter::filter::Filter;
+    use crate::inter::capture_source::CaptureSource;
+    use crate::packet::layers::ethernet_frame::ethernet_frame::EthernetFrame;
+    use crate::packet::layers::ethernet_frame::inter::ethernet_types::EthernetTypes;
+    use crate::packet::layers::ip::inter::ip_protocols::IpProtocols;
+    use crate::packet::layers::ip::ipv4_layer::Ipv4Layer;
+    use crate::packet::layers::ip::ipv6_layer::Ipv6Layer;
+    use crate::packet::layers::payload::payload_layer::PayloadLayer;
+    use crate::packet::layers::sll2_frame::inter::packet_types::PacketTypes;
+    use crate::packet::layers::sll2_frame::sll2_frame::Sll2Frame;
+    use crate::packet::packet::Packet;
+    use crate::pcap::pcap_replay_source::PcapReplaySource;
+    use crate::filter::inter::bpf_codes::{BPF_A, BPF_ALU, BPF_DIV, BPF_IMM, BPF_JA, BPF_JMP, BPF_K, BPF_LD, BPF_MEM, BPF_RET};
+    use crate::pcap::inter::pcapng_options::PcapNgOptions;
     use crate::pcap::pcap::Pcap;
     use crate::pcap::pcapng::PcapNg;
+    use crate::utils::data_link_types::DataLinkTypes;
+    use crate::utils::sock_filter::SockFilter;
+
+    //ETHERNET BROADCAST, IPV4 10.0.0.1 -> 10.0.0.2, UDP 3471 -> 53, NO PAYLOAD
+    const UDP_FRAME: [u8; 42] = [
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x08, 0x00,
+        0x45, 0x00, 0x00, 0x1c, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x01, 0x0a, 0x00, 0x00, 0x02,
+        0x0d, 0x8f, 0x00, 0x35, 0x00, 0x08, 0x00, 0x00
+    ];
+
+    //LOOPBACK ETHERNET, IPV6 ::1 -> ::1, TCP SYN 4242 -> 4243
+    const TCP6_FRAME: [u8; 74] = [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x86, 0xdd,
+        0x60, 0x00, 0x00, 0x00, 0x00, 0x14, 0x06, 0x40,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        0x10, 0x92, 0x10, 0x93, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x50, 0x02, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00
+    ];
 
     //#[test]
     //fn pcap() {
@@ -71,6 +107,475 @@ mod tests {
         println!("{:?}", devices);
     }
 
+    #[test]
+    fn filter() {
+        for expression in ["", "tcp port 443 and not host 10.0.0.1", "udp portrange 1000-2000", "vlan 10 and src net 192.168.0.0/16",
+                           "ether host 00:11:22:33:44:55", "ip6 and dst host fe80::1", "tcp[tcpflags] & (tcp-syn|tcp-ack) != 0",
+                           "port http or 53", "ip proto \\tcp", "(ip[2:2] - ((ip[0] & 0xf) << 2)) > 20"] {
+            let filter = Filter::compile(expression, DataLinkTypes::En10mb).unwrap();
+            assert!(!filter.get_program().is_empty());
+        }
+
+        for expression in ["tcp port", "host example.com", "ip[0:3] = 1", "net 10.0.0.1/8", "port 80 and or"] {
+            assert!(Filter::compile(expression, DataLinkTypes::En10mb).is_err());
+        }
+    }
+
+    #[test]
+    fn bpf_vm() {
+        let frame = UDP_FRAME;
+        assert!(Filter::compile("udp dst port 53 and src host 10.0.0.1", DataLinkTypes::En10mb).unwrap().matches(&frame));
+        assert!(Filter::compile("ether broadcast and len > 40", DataLinkTypes::En10mb).unwrap().matches(&frame));
+        assert!(!Filter::compile("tcp or udp port 80", DataLinkTypes::En10mb).unwrap().matches(&frame));
+        assert!(!Filter::compile("udp", DataLinkTypes::En10mb).unwrap().matches(&frame[..20]));
+
+        let mut tagged = frame[..12].to_vec();
+        tagged.extend_from_slice(&[0x81, 0x00, 0x00, 0x0a]);
+        tagged.extend_from_slice(&frame[12..]);
+        assert!(Filter::compile("vlan 10 and udp dst port 53", DataLinkTypes::En10mb).unwrap().matches(&tagged));
+        assert!(!Filter::compile("vlan 10 and udp dst port 53", DataLinkTypes::En10mb).unwrap().matches(&frame));
+
+        let mut pcap = Pcap::new();
+        pcap.set_data_link_type(DataLinkTypes::En10mb);
+        let mut packet = Packet::new(DataLinkTypes::En10mb, 0, &frame).unwrap();
+        packet.set_original_length(1500);
+        pcap.add_packet(packet);
+        assert_eq!(pcap.filter(Filter::compile("greater 1000", DataLinkTypes::En10mb).unwrap().get_program()).unwrap().len(), 1);
+
+        let kernel = Filter::compile_for_kernel("vlan 10 and udp dst port 53", DataLinkTypes::En10mb).unwrap();
+        assert!(kernel.matches_with_vlan(&frame, Some(10)));
+        assert!(!kernel.matches_with_vlan(&frame, Some(11)));
+        assert!(!kernel.matches_with_vlan(&frame, None));
+
+        assert!(BpfVm::new(&[SockFilter::new(BPF_JMP | BPF_JA, 0, 0, 1), SockFilter::new(BPF_RET | BPF_K, 0, 0, 0)]).is_err());
+        assert!(BpfVm::new(&[SockFilter::new(BPF_ALU | BPF_DIV | BPF_K, 0, 0, 0), SockFilter::new(BPF_RET | BPF_K, 0, 0, 0)]).is_err());
+        assert!(BpfVm::new(&[SockFilter::new(BPF_LD | BPF_MEM, 0, 0, 3), SockFilter::new(BPF_RET | BPF_A, 0, 0, 0)]).is_err());
+        assert!(BpfVm::new(&[SockFilter::new(BPF_LD | BPF_IMM, 0, 0, 7)]).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn break_loop() {
+        let break_loop = crate::break_loop::BreakLoop::new().unwrap();
+        assert!(break_loop.check().is_ok());
+
+        break_loop.break_loop().unwrap();
+        let error = break_loop.check().unwrap_err();
+        assert!(crate::break_loop::BreakLoop::is_break(&error));
+        assert_ne!(error.kind(), ErrorKind::Interrupted);
+
+        break_loop.reset().unwrap();
+        assert!(break_loop.check().is_ok());
+    }
+
+    //NETLINK HEADER FOLLOWED BY ITS ATTRIBUTES, EACH PADDED TO 4 BYTES
+    #[cfg(target_os = "linux")]
+    fn rtnetlink_message<T>(header: &T, attributes: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut buf = crate::linux::netlink::as_bytes(header).to_vec();
+        for (_type, data) in attributes {
+            buf.extend_from_slice(&((data.len() + 4) as u16).to_ne_bytes());
+            buf.extend_from_slice(&_type.to_ne_bytes());
+            buf.extend_from_slice(data);
+            buf.resize(buf.len().div_ceil(4) * 4, 0);
+        }
+        buf
+    }
+
+    #[cfg(target_os = "linux")]
+    fn rtnetlink_link(index: i32, name: &str, flags: u32, mtu: u32) -> Device {
+        use crate::linux::sys::{IfInfoMsg, IFLA_ADDRESS, IFLA_ALLMULTI, IFLA_IFNAME, IFLA_MTU, IFLA_OPERSTATE, IFLA_PROMISCUITY};
+
+        let header = IfInfoMsg { ifi_family: 0, ifi_pad: 0, ifi_type: 1, ifi_index: index, ifi_flags: flags, ifi_change: 0 };
+        let payload = rtnetlink_message(&header, &[
+            (IFLA_IFNAME, format!("{}\0", name).into_bytes()),
+            (IFLA_ADDRESS, vec![0x02, 0x00, 0x00, 0x00, 0x00, 0x07]),
+            (IFLA_MTU, mtu.to_ne_bytes().to_vec()),
+            (IFLA_OPERSTATE, vec![if flags & 0x1 != 0 { 6 } else { 2 }]),
+            (IFLA_PROMISCUITY, 0u32.to_ne_bytes().to_vec()),
+            (IFLA_ALLMULTI, 0u32.to_ne_bytes().to_vec())
+        ]);
+
+        Device::from_link(&payload, false).unwrap()
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn rtnetlink_parsing() {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+        use crate::devices::parse_address;
+        use crate::linux::netlink::{parse_attributes, read_header};
+        use crate::linux::sys::{IfAddrMsg, IfInfoMsg, IFA_ADDRESS, IFA_BROADCAST, IFA_LOCAL, IFLA_IFNAME};
+        use crate::utils::oper_states::OperStates;
+
+        //A LENGTH PAST THE BUFFER OR SHORTER THAN THE HEADER ENDS THE WALK, EARLIER ATTRIBUTES SURVIVE
+        let mut attributes = rtnetlink_message(&(), &[(1, vec![0xaa, 0xbb]), (2, vec![0xcc; 4])]);
+        assert_eq!(parse_attributes(&attributes), vec![(1, &[0xaa, 0xbb][..]), (2, &[0xcc; 4][..])]);
+        attributes.extend_from_slice(&[0x40, 0x00, 0x03, 0x00, 0xdd, 0xdd, 0xdd, 0xdd]);
+        assert_eq!(parse_attributes(&attributes).len(), 2);
+        attributes.truncate(12);
+        attributes.extend_from_slice(&[0x02, 0x00, 0x03, 0x00]);
+        assert_eq!(parse_attributes(&attributes).len(), 2);
+        assert!(read_header::<IfInfoMsg>(&[0; 15]).is_none());
+
+        let device = rtnetlink_link(7, "eth9", 0x1 | 0x40, 1500);
+        assert_eq!(device.get_name(), "eth9");
+        assert_eq!(device.get_index(), 7);
+        assert_eq!(device.get_data_link_type(), DataLinkTypes::En10mb);
+        assert_eq!(device.get_mac().unwrap().to_string(), "02:00:00:00:00:07");
+        assert_eq!(device.get_mtu(), 1500);
+        assert_eq!(device.get_oper_state(), OperStates::Up);
+        assert!(device.is_up());
+
+        let header = IfInfoMsg { ifi_family: 0, ifi_pad: 0, ifi_type: 1, ifi_index: 7, ifi_flags: 0, ifi_change: 0 };
+        assert!(Device::from_link(&rtnetlink_message(&header, &[]), false).is_none());
+        assert!(Device::from_link(&rtnetlink_message(&header, &[(IFLA_IFNAME, b"eth9\0".to_vec())])[..12], false).is_none());
+
+        let header = IfAddrMsg { ifa_family: 2, ifa_prefixlen: 24, ifa_flags: 0, ifa_scope: 0, ifa_index: 7 };
+        let (index, address) = parse_address(&rtnetlink_message(&header, &[
+            (IFA_ADDRESS, vec![10, 0, 0, 2]),
+            (IFA_LOCAL, vec![10, 0, 0, 2]),
+            (IFA_BROADCAST, vec![10, 0, 0, 255])
+        ])).unwrap();
+        assert_eq!(index, 7);
+        assert_eq!(address.get_address(), IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)));
+        assert_eq!(address.get_prefix_length(), 24);
+        assert_eq!(address.get_broadcast(), Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 255))));
+        assert_eq!(address.get_peer(), None);
+
+        let (_, address) = parse_address(&rtnetlink_message(&header, &[
+            (IFA_ADDRESS, vec![10, 0, 0, 1]),
+            (IFA_LOCAL, vec![10, 0, 0, 2])
+        ])).unwrap();
+        assert_eq!(address.get_peer(), Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1))));
+
+        let header = IfAddrMsg { ifa_family: 10, ifa_prefixlen: 64, ifa_flags: 0, ifa_scope: 0, ifa_index: 7 };
+        let (_, address) = parse_address(&rtnetlink_message(&header, &[(IFA_ADDRESS, Ipv6Addr::LOCALHOST.octets().to_vec())])).unwrap();
+        assert_eq!(address.get_address(), IpAddr::V6(Ipv6Addr::LOCALHOST));
+
+        //WRONG SIZED ADDRESSES ARE SKIPPED, NOTHING LEFT MEANS NO ADDRESS
+        assert!(parse_address(&rtnetlink_message(&header, &[(IFA_ADDRESS, vec![10, 0, 0, 2])])).is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn device_monitor_diff() {
+        use std::collections::VecDeque;
+        use std::net::{IpAddr, Ipv4Addr};
+        use crate::device_events::DeviceEvents;
+        use crate::device_monitor::diff;
+        use crate::utils::device_address::DeviceAddress;
+
+        let up = rtnetlink_link(7, "eth9", 0x1 | 0x40, 1500);
+        let mut events = VecDeque::new();
+
+        diff(None, &up, &mut events);
+        assert!(matches!(events.pop_front(), Some(DeviceEvents::Added(device)) if device.get_name() == "eth9"));
+
+        diff(Some(&up), &up, &mut events);
+        assert!(events.is_empty());
+
+        diff(Some(&up), &rtnetlink_link(7, "wan0", 0x1 | 0x40, 1500), &mut events);
+        assert!(matches!(events.pop_front(), Some(DeviceEvents::Renamed(device, previous)) if device.get_name() == "wan0" && previous == "eth9"));
+        assert!(events.is_empty());
+
+        let down = rtnetlink_link(7, "eth9", 0, 1500);
+        diff(Some(&up), &down, &mut events);
+        assert!(matches!(events.pop_front(), Some(DeviceEvents::Down(_))));
+        diff(Some(&down), &up, &mut events);
+        assert!(matches!(events.pop_front(), Some(DeviceEvents::Up(_))));
+        assert!(events.is_empty());
+
+        diff(Some(&up), &rtnetlink_link(7, "eth9", 0x1 | 0x40, 9000), &mut events);
+        assert!(matches!(events.pop_front(), Some(DeviceEvents::Changed(device)) if device.get_mtu() == 9000));
+
+        let address = DeviceAddress::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)), 24, None, None);
+        let mut addressed = up.clone();
+        addressed.add_address(address);
+
+        diff(Some(&up), &addressed, &mut events);
+        assert!(matches!(events.pop_front(), Some(DeviceEvents::AddressAdded(_, added)) if added == address));
+        diff(Some(&addressed), &up, &mut events);
+        assert!(matches!(events.pop_front(), Some(DeviceEvents::AddressRemoved(_, removed)) if removed == address));
+        assert!(events.is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn default_route_parsing() {
+        use crate::devices::parse_default_route;
+        use crate::linux::sys::{RtMsg, RtNextHop, AF_INET, AF_INET6, RTA_MULTIPATH, RTA_OIF, RTA_PRIORITY, RTA_TABLE};
+
+        let route = |family: i64, dst_len: u8, table: u8, _type: u8| RtMsg {
+            rtm_family: family as u8,
+            rtm_dst_len: dst_len,
+            rtm_src_len: 0,
+            rtm_tos: 0,
+            rtm_table: table,
+            rtm_protocol: 0,
+            rtm_scope: 0,
+            rtm_type: _type,
+            rtm_flags: 0
+        };
+
+        let payload = rtnetlink_message(&route(AF_INET, 0, 254, 1), &[
+            (RTA_PRIORITY, 100u32.to_ne_bytes().to_vec()),
+            (RTA_OIF, 2i32.to_ne_bytes().to_vec())
+        ]);
+        assert_eq!(parse_default_route(&payload), Some((AF_INET as u8, 100, vec![2])));
+
+        //ECMP DEFAULT, ONE INDEX PER NEXT HOP
+        let mut hops = Vec::new();
+        for index in [3, 4] {
+            hops.extend_from_slice(crate::linux::netlink::as_bytes(&RtNextHop { rtnh_len: 8, rtnh_flags: 0, rtnh_hops: 0, rtnh_ifindex: index }));
+        }
+        let payload = rtnetlink_message(&route(AF_INET6, 0, 254, 1), &[(RTA_MULTIPATH, hops)]);
+        assert_eq!(parse_default_route(&payload), Some((AF_INET6 as u8, 0, vec![3, 4])));
+
+        //NOT A DEFAULT, NOT UNICAST, NOT IN THE MAIN TABLE
+        let oif = [(RTA_OIF, 2i32.to_ne_bytes().to_vec())];
+        assert_eq!(parse_default_route(&rtnetlink_message(&route(AF_INET, 24, 254, 1), &oif)), None);
+        assert_eq!(parse_default_route(&rtnetlink_message(&route(AF_INET, 0, 254, 2), &oif)), None);
+        assert_eq!(parse_default_route(&rtnetlink_message(&route(AF_INET, 0, 255, 1), &oif)), None);
+        assert_eq!(parse_default_route(&rtnetlink_message(&route(AF_INET, 0, 252, 1), &[(RTA_TABLE, 254u32.to_ne_bytes().to_vec())])), Some((AF_INET as u8, 0, vec![])));
+        assert_eq!(parse_default_route(&[0; 8]), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn tx_ring_discard() {
+        use std::collections::VecDeque;
+        use crate::linux::sys::{TP_STATUS_AVAILABLE, TP_STATUS_SEND_REQUEST, TP_STATUS_WRONG_FORMAT};
+        use crate::tx_ring::{TxRing, TxRingConfig};
+
+        //TWO BLOCKS OF TWO FRAMES SO THE SHIFT CROSSES A BLOCK AND WRAPS
+        let mut ring = TxRing::from_heap(&TxRingConfig::new(256, 2, 128)).unwrap();
+        assert_eq!(ring.get_frame_count(), 4);
+
+        assert_eq!(ring.push(&[0; 60]).unwrap(), Some(0));
+        ring.set_status(0, TP_STATUS_AVAILABLE);
+
+        let mut pending = VecDeque::new();
+        for (i, frame) in [[1u8; 64], [2; 64], [3; 64], [4; 64]].iter().enumerate() {
+            assert_eq!(ring.push(&frame[..60 + i]).unwrap(), Some((i + 1) % 4));
+            pending.push_back(Some((i + 1) % 4));
+        }
+        assert_eq!(ring.push(&[5; 60]).unwrap(), None);
+
+        //KERNEL REJECTS THE FIRST QUEUED FRAME, THE OTHER THREE MOVE UP AND THE LAST SLOT FREES
+        ring.set_status(1, TP_STATUS_SEND_REQUEST | TP_STATUS_WRONG_FORMAT);
+        let index = pending.pop_front().unwrap().unwrap();
+        ring.discard(index, &mut pending);
+
+        assert_eq!(pending, VecDeque::from([Some(1), Some(2), Some(3)]));
+        for (index, frame) in [(1, [2u8; 64]), (2, [3; 64]), (3, [4; 64])] {
+            assert_eq!(ring.frame_data(index), &frame[..60 + index]);
+            assert_eq!(ring.status(index), TP_STATUS_SEND_REQUEST);
+        }
+        assert_eq!(ring.status(0), TP_STATUS_AVAILABLE);
+        assert_eq!(ring.push(&[5; 60]).unwrap(), Some(0));
+    }
+
+    #[test]
+    fn truncated_packet() {
+        let frame = UDP_FRAME;
+
+        assert_eq!(Packet::new(DataLinkTypes::En10mb, 0, &frame).unwrap().get_captured_length(), frame.len());
+        assert_eq!(Packet::new(DataLinkTypes::En10mb, 0, &frame[..10]).unwrap_err().kind(), ErrorKind::InvalidData);
+
+        //IP HEADER CUT SHORT, THE REST IS KEPT AS PAYLOAD
+        let packet = Packet::new(DataLinkTypes::En10mb, 0, &frame[..30]).unwrap();
+        assert_eq!(packet.get_frame::<EthernetFrame>().get_data::<PayloadLayer>().unwrap().get_data(), &frame[14..30]);
+        assert_eq!(packet.to_bytes(), frame[..30].to_vec());
+        assert_eq!(Packet::new(DataLinkTypes::Raw, 0, &frame[14..30]).unwrap().to_bytes(), frame[14..30].to_vec());
+
+        //TCP HEADER CUT SHORT BY A 64 BYTE SNAPLEN
+        let packet = Packet::new(DataLinkTypes::En10mb, 0, &TCP6_FRAME[..64]).unwrap();
+        let ipv6 = packet.get_frame::<EthernetFrame>().get_data::<Ipv6Layer>().unwrap();
+        assert_eq!(ipv6.get_data::<PayloadLayer>().unwrap().get_data(), &TCP6_FRAME[54..64]);
+        assert_eq!(packet.to_bytes(), TCP6_FRAME[..64].to_vec());
+    }
+
+    #[test]
+    fn pcap_truncated_records() {
+        let path = std::env::temp_dir().join(format!("rlibpcap-truncated-{}.pcap", std::process::id()));
+
+        let mut pcap = Pcap::new();
+        pcap.set_data_link_type(DataLinkTypes::En10mb);
+        for snaplen in [64, 30, TCP6_FRAME.len()] {
+            let mut packet = Packet::new(DataLinkTypes::En10mb, 0, &TCP6_FRAME[..snaplen]).unwrap();
+            packet.set_original_length(TCP6_FRAME.len());
+            pcap.add_packet(packet);
+        }
+        pcap.to_file(path.to_str().unwrap()).unwrap();
+
+        let loaded = Pcap::from_file(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        let packets = loaded.unwrap().get_packets();
+        assert_eq!(packets.iter().map(|p| p.get_captured_length()).collect::<Vec<_>>(), vec![64, 30, 74]);
+        assert!(packets.iter().all(|p| p.get_original_length() == TCP6_FRAME.len()));
+        assert_eq!(packets[0].to_bytes(), TCP6_FRAME[..64].to_vec());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn snaplen_capture() {
+        let lo = Device::list().unwrap().into_iter().find(|d| d.get_name().eq("lo")).unwrap();
+
+        let mut builder = crate::capture::Capture::from_device(&lo);
+        builder.set_snaplen(64).unwrap();
+        builder.set_filter(Filter::compile("ip6 and tcp dst port 4243", DataLinkTypes::En10mb).unwrap().get_program());
+        builder.set_timeout(Duration::from_secs(1));
+        let capture = builder.open().unwrap();
+
+        capture.send(Packet::new(DataLinkTypes::En10mb, 0, &TCP6_FRAME).unwrap()).unwrap();
+
+        let (_, packet) = capture.recv().unwrap();
+        assert_eq!(packet.get_captured_length(), 64);
+        assert_eq!(packet.get_original_length(), TCP6_FRAME.len());
+        assert_eq!(packet.to_bytes(), TCP6_FRAME[..64].to_vec());
+    }
+
+    #[test]
+    fn sll2_unknown_types() {
+        let frame = [
+            0x88, 0xcc, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x01, 0x05, 0x06, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x00, 0x00,
+            0x02, 0x07, 0x04, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55
+        ];
+
+        let packet = Packet::new(DataLinkTypes::Sll2, 0, &frame).unwrap();
+        let sll2 = packet.get_frame::<Sll2Frame>();
+        assert_eq!(sll2.get_protocol(), EthernetTypes::Unknown(0x88cc));
+        assert_eq!(sll2.get_packet_type(), PacketTypes::Unknown(5));
+        assert_eq!(packet.to_bytes(), frame.to_vec());
+    }
+
+    #[test]
+    fn ip_unknown_protocols() {
+        //ESP OVER IPV4, SPI 0x100 SEQUENCE 1
+        let mut frame = UDP_FRAME[..34].to_vec();
+        frame[23] = 50;
+        frame.extend_from_slice(&[0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x01, 0xde, 0xad, 0xbe, 0xef]);
+
+        let packet = Packet::new(DataLinkTypes::En10mb, 0, &frame).unwrap();
+        let ipv4 = packet.get_frame::<EthernetFrame>().get_data::<Ipv4Layer>().unwrap();
+        assert_eq!(ipv4.get_protocol(), IpProtocols::Unknown(50));
+        assert_eq!(ipv4.get_data::<PayloadLayer>().unwrap().get_data(), &frame[34..]);
+        assert_eq!(packet.to_bytes(), frame);
+
+        //IPV6 FRAGMENT HEADER AND AN UNKNOWN VERSION NIBBLE
+        let mut frame = TCP6_FRAME.to_vec();
+        frame[20] = 44;
+        assert_eq!(Packet::new(DataLinkTypes::En10mb, 0, &frame).unwrap().to_bytes(), frame);
+
+        frame[14] = 0x50;
+        assert_eq!(Packet::new(DataLinkTypes::En10mb, 0, &frame).unwrap().to_bytes(), frame);
+    }
+
+    fn pcapng_block(block_type: u32, body: &[u8]) -> Vec<u8> {
+        let mut body = body.to_vec();
+        body.resize(body.len().div_ceil(4) * 4, 0);
+
+        let length = (body.len() + 12) as u32;
+        let mut block = block_type.to_le_bytes().to_vec();
+        block.extend_from_slice(&length.to_le_bytes());
+        block.extend(body);
+        block.extend_from_slice(&length.to_le_bytes());
+        block
+    }
+
+    //SHB, AN ETHERNET IDB WITH NANOSECOND TIMESTAMPS, AN UNPARSED ISB, THEN ONE EPB PER FRAME
+    fn pcapng_file(frames: &[(u64, Vec<u8>, u32)]) -> Vec<u8> {
+        let mut shb = vec![0x4d, 0x3c, 0x2b, 0x1a, 0x01, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+        shb.extend_from_slice(&[0x04, 0x00, 0x08, 0x00]);
+        shb.extend_from_slice(b"rlibpcap");
+        shb.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+
+        let mut file = pcapng_block(0x0a0d0d0a, &shb);
+        file.extend(pcapng_block(1, &[0x01, 0x00, 0x00, 0x00, 0xff, 0xff, 0x00, 0x00, 0x09, 0x00, 0x01, 0x00, 0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]));
+        file.extend(pcapng_block(5, &[0x00; 12]));
+
+        for (timestamp, frame, original_length) in frames {
+            let mut epb = 0u32.to_le_bytes().to_vec();
+            epb.extend_from_slice(&((timestamp >> 32) as u32).to_le_bytes());
+            epb.extend_from_slice(&(*timestamp as u32).to_le_bytes());
+            epb.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            epb.extend_from_slice(&original_length.to_le_bytes());
+            epb.extend_from_slice(frame);
+            file.extend(pcapng_block(6, &epb));
+        }
+
+        file
+    }
+
+    #[test]
+    fn pcapng_replay() {
+        let mut frames = Vec::new();
+        for (i, port) in [53u16, 80, 53].iter().enumerate() {
+            let mut frame = UDP_FRAME.to_vec();
+            frame[36..38].copy_from_slice(&port.to_be_bytes());
+            frames.push((1_000_000_000_123 + i as u64, frame, UDP_FRAME.len() as u32));
+        }
+        frames.push((1_000_000_000_200, TCP6_FRAME[..64].to_vec(), TCP6_FRAME.len() as u32));
+
+        let path = std::env::temp_dir().join(format!("rlibpcap-replay-{}.pcapng", std::process::id()));
+        std::fs::write(&path, pcapng_file(&frames)).unwrap();
+        let pcapng = PcapNg::from_file(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        let pcapng = pcapng.unwrap();
+        assert_eq!(pcapng.get_data_link_type(), DataLinkTypes::En10mb);
+        assert_eq!(pcapng.get_option(PcapNgOptions::Application).unwrap(), "rlibpcap");
+        assert_eq!(pcapng.total_packets(), frames.len());
+        assert_eq!(pcapng.get_packet(3).get_original_length(), TCP6_FRAME.len());
+        assert_eq!(pcapng.filter(Filter::compile("udp dst port 53", DataLinkTypes::En10mb).unwrap().get_program()).unwrap().len(), 2);
+        assert_eq!(pcapng.filter(Filter::compile("greater 70", DataLinkTypes::En10mb).unwrap().get_program()).unwrap().len(), 1);
+
+        let mut source = PcapReplaySource::from_pcapng(pcapng);
+        assert_eq!(source.get_data_link_type(), DataLinkTypes::En10mb);
+        source.open().unwrap();
+
+        for (timestamp, frame, _) in &frames {
+            let packet = source.try_recv().unwrap();
+            assert_eq!(packet.get_frame_time(), *timestamp as u128);
+            assert_eq!(packet.to_bytes(), *frame);
+        }
+        assert_eq!(source.try_recv().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn pcap_replay() {
+        let mut frame = UDP_FRAME.to_vec();
+
+        let mut pcap = Pcap::new();
+        pcap.set_data_link_type(DataLinkTypes::En10mb);
+        for (i, port) in [53u16, 80, 53].iter().enumerate() {
+            frame[36..38].copy_from_slice(&port.to_be_bytes());
+            pcap.add_packet(Packet::new(DataLinkTypes::En10mb, 1_000_000_000 + i as u128 * 50_000_000, &frame).unwrap());
+        }
+
+        let mut source = PcapReplaySource::from_pcap(pcap);
+        assert_eq!(source.get_data_link_type(), DataLinkTypes::En10mb);
+        source.set_filter(Filter::compile("udp dst port 53", DataLinkTypes::En10mb).unwrap().get_program()).unwrap();
+        source.set_original_timing(true);
+
+        let start = Instant::now();
+        assert!(source.recv().is_ok());
+        assert_eq!(source.try_recv().unwrap_err().kind(), ErrorKind::WouldBlock);
+        assert!(source.recv().is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(source.recv().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(source.stats().unwrap().get_received(), 2);
+        assert_eq!(source.stats().unwrap().get_interface_received(), 0);
+        assert_eq!(source.get_position(), 3);
+
+        source.open().unwrap();
+        source.set_original_timing(false);
+        assert_eq!(source.remaining(), 3);
+        assert_eq!(source.send(source.try_recv().unwrap()).unwrap(), frame.len());
+        assert_eq!(source.get_sent().len(), 1);
+    }
+
     /*
     #[test]
     fn capture() {
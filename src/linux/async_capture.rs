@@ -0,0 +1,82 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use futures_core::Stream;
+use tokio::io::unix::AsyncFd;
+use tokio::io::Interest;
+use crate::devices::Device;
+use crate::linux::capture::Capture;
+use crate::linux::sys::SockAddrIn;
+use crate::packet::packet::Packet;
+
+#[derive(Debug)]
+pub struct AsyncCapture {
+    inner: AsyncFd<Capture>
+}
+
+impl AsyncCapture {
+
+    pub fn new(capture: Capture) -> io::Result<Self> {
+        Ok(Self {
+            inner: AsyncFd::with_interest(capture, Interest::READABLE | Interest::WRITABLE)?
+        })
+    }
+
+    pub async fn recv(&self) -> io::Result<(SockAddrIn, Packet)> {
+        loop {
+            let mut guard = self.inner.readable().await?;
+
+            if let Ok(res) = guard.try_io(|inner| inner.get_ref().try_recv()) {
+                return res;
+            }
+        }
+    }
+
+    pub async fn send(&self, packet: Packet) -> io::Result<usize> {
+        loop {
+            let mut guard = self.inner.writable().await?;
+
+            if let Ok(res) = guard.try_io(|inner| inner.get_ref().try_send(packet.clone())) {
+                return res;
+            }
+        }
+    }
+
+    pub async fn send_to(&self, packet: Packet, device: &Device) -> io::Result<usize> {
+        loop {
+            let mut guard = self.inner.writable().await?;
+
+            if let Ok(res) = guard.try_io(|inner| inner.get_ref().try_send_to(packet.clone(), device)) {
+                return res;
+            }
+        }
+    }
+
+    pub fn get_ref(&self) -> &Capture {
+        self.inner.get_ref()
+    }
+
+    pub fn into_inner(self) -> Capture {
+        self.inner.into_inner()
+    }
+
+    pub fn close(self) {
+        //DEREGISTER FROM THE REACTOR BEFORE THE FD IS RELEASED
+        self.into_inner().close();
+    }
+}
+
+impl Stream for AsyncCapture {
+
+    type Item = io::Result<Packet>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            let mut guard = ready!(self.inner.poll_read_ready(cx))?;
+
+            if let Ok(res) = guard.try_io(|inner| inner.get_ref().try_recv()) {
+                return Poll::Ready(Some(res.map(|(_, packet)| packet)));
+            }
+        }
+    }
+}
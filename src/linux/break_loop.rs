@@ -0,0 +1,98 @@
+use std::{fmt, io};
+use std::error::Error;
+use std::os::fd::RawFd;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::linux::sys::{close, eventfd, read, write, EAGAIN, EFD_CLOEXEC, EFD_NONBLOCK};
+
+#[derive(Debug)]
+struct Signal {
+    fd: RawFd,
+    broken: AtomicBool
+}
+
+impl Drop for Signal {
+
+    fn drop(&mut self) {
+        unsafe { close(self.fd) };
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct LoopBroken;
+
+impl fmt::Display for LoopBroken {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Capture loop was broken")
+    }
+}
+
+impl Error for LoopBroken {}
+
+#[derive(Debug, Clone)]
+pub struct BreakLoop {
+    signal: Arc<Signal>
+}
+
+impl BreakLoop {
+
+    pub(crate) fn new() -> io::Result<Self> {
+        let fd = unsafe { eventfd(0, EFD_NONBLOCK | EFD_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::from_raw_os_error(-fd as i32));
+        }
+
+        Ok(Self {
+            signal: Arc::new(Signal {
+                fd: fd as RawFd,
+                broken: AtomicBool::new(false)
+            })
+        })
+    }
+
+    pub fn break_loop(&self) -> io::Result<()> {
+        self.signal.broken.store(true, Ordering::SeqCst);
+
+        //COUNTER IS NEVER DRAINED UNTIL RESET SO EVERY WAITER WAKES
+        let res = unsafe { write(self.signal.fd, &1u64.to_ne_bytes()) };
+        if res < 0 && -res as i32 != EAGAIN {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        Ok(())
+    }
+
+    pub fn reset(&self) -> io::Result<()> {
+        self.signal.broken.store(false, Ordering::SeqCst);
+
+        let mut counter = [0u8; 8];
+        let res = unsafe { read(self.signal.fd, &mut counter) };
+        if res < 0 && -res as i32 != EAGAIN {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        Ok(())
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.signal.broken.load(Ordering::SeqCst)
+    }
+
+    pub fn is_break(error: &io::Error) -> bool {
+        error.get_ref().is_some_and(|e| e.is::<LoopBroken>())
+    }
+
+    //NOT INTERRUPTED, RETRY LOOPS TREAT THAT AS TRY AGAIN AND WOULD NEVER STOP
+    pub(crate) fn check(&self) -> io::Result<()> {
+        if self.is_broken() {
+            return Err(io::Error::other(LoopBroken));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn get_fd(&self) -> RawFd {
+        self.signal.fd
+    }
+}
@@ -1,52 +1,224 @@
-use std::{io, mem};
-use std::os::fd::RawFd;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::{fs, io, mem, ptr};
+use std::fs::OpenOptions;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use crate::devices::Device;
-use crate::linux::sys::{bind, close, recvfrom, sendto, setsockopt, socket, syscall, IfreqName, MSG_DONTWAIT};
+use crate::inter::capture_source::CaptureSource;
+use crate::linux::break_loop::BreakLoop;
+use crate::linux::capture_builder::CaptureBuilder;
+use crate::linux::fanout_modes::FanoutModes;
+use crate::linux::net_namespaces::NetNamespaces;
+use crate::linux::packet_buf::PacketBuf;
+use crate::linux::rx_ring::{RxRing, RxRingConfig};
+use crate::linux::tun_modes::TunModes;
+use crate::linux::sys::{bind, dup, getsockopt, ioctl, poll, recvmmsg, recvmsg, sendto, setsockopt, socket, CmsgHdr, HwtstampConfig, IfreqData, IfreqName, IoVec, MmsgHdr, MsgHdr, PacketMreq, TimeSpec, TpacketAuxdata, TpacketStatsV3, PACKET_AUXDATA, TP_STATUS_CSUMNOTREADY, TP_STATUS_CSUM_VALID, TP_STATUS_VLAN_TPID_VALID, TP_STATUS_VLAN_VALID, HWTSTAMP_FILTER_ALL, HWTSTAMP_TX_OFF, PACKET_TIMESTAMP, SCM_TIMESTAMPING, SCM_TIMESTAMPNS, SIOCSHWTSTAMP, SOF_TIMESTAMPING_RAW_HARDWARE, SOF_TIMESTAMPING_RX_HARDWARE, SOF_TIMESTAMPING_RX_SOFTWARE, SOF_TIMESTAMPING_SOFTWARE, SO_TIMESTAMPING, SO_TIMESTAMPNS, PollFd, SockFprog, EAGAIN, EINTR, MSG_DONTWAIT, MSG_TRUNC, MSG_WAITFORONE, PACKET_ADD_MEMBERSHIP, PACKET_DROP_MEMBERSHIP, PACKET_FANOUT, PACKET_FANOUT_FLAG_DEFRAG, PACKET_IGNORE_OUTGOING, PACKET_MR_ALLMULTI, PACKET_MR_PROMISC, PACKET_STATISTICS, POLLERR, POLLIN, POLLOUT, SOL_PACKET, SO_ATTACH_FILTER, SO_DETACH_FILTER, SO_LOCK_FILTER};
 use crate::packet::packet::Packet;
-use crate::linux::sys::{SockAddrIn, AF_PACKET, ETH_P_ALL, IFNAMSIZ, SOCK_RAW, SOL_SOCKET, SO_BINDTODEVICE, SYS_BIND, SYS_RECV_FROM, SYS_SENDTO, SYS_SET_SOCK_OPT, SYS_SOCKET};
+use crate::linux::sys::{read, write, IfreqFlags, SockAddrIn, AF_PACKET, ARPHRD_ETHER, ARPHRD_NONE, ETH_P_ALL, IFF_NO_PI, IFNAMSIZ, O_NONBLOCK, SOCK_DGRAM, SOCK_NONBLOCK, SOCK_RAW, SOL_SOCKET, SO_BINDTODEVICE, TUNATTACHFILTER, TUNDETACHFILTER, TUNSETIFF};
+use crate::packet::layers::ethernet_frame::inter::ethernet_types::EthernetTypes;
+use crate::packet::layers::sll2_frame::inter::packet_types::PacketTypes;
+use crate::packet::layers::sll2_frame::sll2_frame::SLL2_FRAME_LEN;
+use crate::utils::capture_directions::CaptureDirections;
+use crate::utils::capture_stats::CaptureStats;
+use crate::utils::checksum_statuses::ChecksumStatuses;
 use crate::utils::data_link_types::DataLinkTypes;
+use crate::utils::sock_filter::SockFilter;
+use crate::utils::timestamp_sources::TimestampSources;
 
-#[derive(Debug, Clone)]
+pub use crate::utils::sock_filter::DEFAULT_SNAPLEN;
+
+const TUN_BUFFER_LEN: usize = 1 << 17;
+
+#[derive(Debug)]
 pub struct Capture {
-    fd: RawFd,
-    device: Option<Device>
+    fd: OwnedFd,
+    device: Option<Device>,
+    netns: Option<NetNamespaces>,
+    ring: Option<Arc<Mutex<RxRing>>>,
+    buffer: Mutex<Vec<u8>>,
+    promiscuous: bool,
+    all_multicast: bool,
+    timestamp_source: TimestampSources,
+    stats: Arc<Mutex<CaptureStats>>,
+    snaplen: u32,
+    cooked: bool,
+    tun: Option<TunModes>,
+    direction: CaptureDirections,
+    timeout: Option<Duration>,
+    break_loop: BreakLoop
 }
 
 impl Capture {
 
-    pub fn any() -> io::Result<Self> {
-        let fd = unsafe { socket(AF_PACKET, SOCK_RAW, ETH_P_ALL.to_be() as i64) };
+    pub fn any() -> CaptureBuilder {
+        CaptureBuilder::any()
+    }
 
-        if fd < 0 {
-            return Err(io::Error::last_os_error());
+    pub fn from_device(device: &Device) -> CaptureBuilder {
+        CaptureBuilder::from_device(device)
+    }
+
+    pub fn in_netns(netns: impl Into<NetNamespaces>, device: &Device) -> CaptureBuilder {
+        let mut builder = CaptureBuilder::from_device(device);
+        builder.set_netns(netns);
+        builder
+    }
+
+    pub fn tun(name: &str, mode: TunModes) -> CaptureBuilder {
+        CaptureBuilder::tun(name, mode)
+    }
+
+    pub(crate) fn open(builder: CaptureBuilder) -> io::Result<Self> {
+        if let Some((name, mode)) = builder.get_tun() {
+            return Self::open_tun(&builder, name, mode);
         }
 
-        Ok(Self {
-            fd: fd as RawFd,
-            device: None
-        })
+        let break_loop = BreakLoop::new()?;
+
+        //A PACKET SOCKET STAYS IN THE NAMESPACE IT WAS CREATED IN, EVERYTHING AFTER CAN RUN FROM HERE
+        let fd = match builder.get_netns() {
+            Some(netns) => netns.run(|| Self::open_socket(builder.is_cooked()))?,
+            None => Self::open_socket(builder.is_cooked())?
+        };
+
+        //FROM HERE ON ANY EARLY RETURN DROPS THE CAPTURE AND CLOSES THE SOCKET
+        let mut capture = Self {
+            fd,
+            device: builder.get_device().cloned(),
+            netns: builder.get_netns().cloned(),
+            ring: None,
+            buffer: Mutex::new(Vec::new()),
+            promiscuous: false,
+            all_multicast: false,
+            timestamp_source: TimestampSources::Software,
+            stats: Arc::new(Mutex::new(CaptureStats::default())),
+            snaplen: builder.get_snaplen(),
+            cooked: builder.is_cooked(),
+            tun: None,
+            direction: CaptureDirections::InOut,
+            timeout: builder.get_timeout(),
+            break_loop
+        };
+
+        capture.enable_auxdata()?;
+        capture.set_timestamp_source(builder.get_timestamp_source())?;
+
+        if let Some(filter) = builder.get_filter() {
+            capture.set_filter(filter)?;
+        }
+
+        capture.bind()?;
+
+        if builder.get_direction() != CaptureDirections::InOut {
+            capture.set_direction(builder.get_direction())?;
+        }
+
+        capture.set_promiscuous(builder.is_promiscuous())?;
+        capture.set_all_multicast(builder.is_all_multicast())?;
+
+        if let Some(config) = builder.get_rx_ring() {
+            capture.set_rx_ring(config)?;
+        }
+
+        if let Some((group_id, mode, defrag)) = builder.get_fanout() {
+            capture.set_fanout(group_id, mode, defrag)?;
+        }
+
+        Ok(capture)
     }
 
-    pub fn from_device(device: &Device) -> io::Result<Self> {
-        let fd = unsafe { socket(AF_PACKET, SOCK_RAW, ETH_P_ALL.to_be() as i64) };
+    fn open_tun(builder: &CaptureBuilder, name: &str, mode: TunModes) -> io::Result<Self> {
+        if builder.is_cooked() || builder.is_promiscuous() || builder.is_all_multicast() || builder.get_rx_ring().is_some()
+                || builder.get_fanout().is_some() || builder.get_timestamp_source() == TimestampSources::Hardware {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "TUN/TAP captures only support filters, directions and timeouts"));
+        }
+
+        let break_loop = BreakLoop::new()?;
 
-        if fd < 0 {
-            return Err(io::Error::last_os_error());
+        //TUNSETIFF CREATES THE DEVICE IN THE NAMESPACE OF THE CALLING THREAD
+        let open = || {
+            let (fd, name) = Self::open_tun_fd(name, mode)?;
+            Ok((fd, Device::from_name(&name)?))
+        };
+
+        let (fd, device) = match builder.get_netns() {
+            Some(netns) => netns.run(open)?,
+            None => open()?
+        };
+
+        let mut capture = Self {
+            fd,
+            device: Some(device),
+            netns: builder.get_netns().cloned(),
+            ring: None,
+            buffer: Mutex::new(Vec::new()),
+            promiscuous: false,
+            all_multicast: false,
+            timestamp_source: TimestampSources::User,
+            stats: Arc::new(Mutex::new(CaptureStats::default())),
+            snaplen: builder.get_snaplen(),
+            cooked: false,
+            tun: Some(mode),
+            direction: CaptureDirections::InOut,
+            timeout: builder.get_timeout(),
+            break_loop
+        };
+
+        if let Some(filter) = builder.get_filter() {
+            capture.set_filter(filter)?;
         }
 
-        Ok(Self {
-            fd: fd as RawFd,
-            device: Some(device.clone())
-        })
+        capture.set_direction(builder.get_direction())?;
+
+        Ok(capture)
     }
 
-    pub fn open(&self) -> io::Result<()> {
-        if self.fd < 0 {
-            return Err(io::Error::last_os_error());
+    fn open_tun_fd(name: &str, mode: TunModes) -> io::Result<(OwnedFd, String)> {
+        let fd = OwnedFd::from(OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(O_NONBLOCK)
+            .open("/dev/net/tun")?);
+
+        let if_name_bytes = name.as_bytes();
+        if if_name_bytes.len() >= IFNAMSIZ {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Interface name too long"));
         }
 
-        if match self.device {
+        let mut ifreq = IfreqFlags {
+            ifr_name: [0; IFNAMSIZ],
+            ifr_flags: mode.get_code() | IFF_NO_PI,
+            ifr_pad: [0; 22]
+        };
+        ifreq.ifr_name[..if_name_bytes.len()].copy_from_slice(if_name_bytes);
+
+        //EMPTY NAME LETS THE KERNEL PICK ONE, AN EXISTING DEVICE OF THE SAME MODE IS ATTACHED TO
+        let res = unsafe { ioctl(fd.as_raw_fd(), TUNSETIFF, &mut ifreq as *mut _ as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        let length = ifreq.ifr_name.iter().position(|b| *b == 0).unwrap_or(IFNAMSIZ);
+        Ok((fd, String::from_utf8_lossy(&ifreq.ifr_name[..length]).to_string()))
+    }
+
+    fn open_socket(cooked: bool) -> io::Result<OwnedFd> {
+        //DGRAM SOCKETS HAND US THE PAYLOAD WITHOUT THE LINK HEADER, SOCKADDR DESCRIBES THE REST
+        let _type = if cooked { SOCK_DGRAM } else { SOCK_RAW };
+
+        let fd = unsafe { socket(AF_PACKET, _type | SOCK_NONBLOCK, ETH_P_ALL.to_be() as i64) };
+        if fd < 0 {
+            return Err(io::Error::from_raw_os_error(-fd));
+        }
+
+        Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+    }
+
+    fn bind(&self) -> io::Result<()> {
+        let res = match self.device {
             Some(ref device) => {
                 let mut ifreq = IfreqName {
                     ifr_name: [0; IFNAMSIZ],
@@ -54,7 +226,6 @@ impl Capture {
 
                 let if_name_bytes = device.get_name().into_bytes();
                 if if_name_bytes.len() >= IFNAMSIZ {
-                    unsafe { close(self.fd) };
                     return Err(io::Error::new(io::ErrorKind::InvalidInput, "Interface name too long"));
                 }
 
@@ -70,89 +241,1031 @@ impl Capture {
                     sll_addr: [0; 8]
                 };
 
-                if unsafe { bind(self.fd, &sockaddr as *const _ as i64, mem::size_of::<SockAddrIn>() as i64) } < 0 {
-                    unsafe { close(self.fd) };
-                    return Err(io::Error::last_os_error());
+                let res = unsafe { bind(self.fd.as_raw_fd(), &sockaddr as *const _ as i64, mem::size_of::<SockAddrIn>() as i64) };
+                if res < 0 {
+                    return Err(io::Error::from_raw_os_error(-res as i32));
                 }
 
                 unsafe {
-                    setsockopt(self.fd, SOL_SOCKET, SO_BINDTODEVICE, ifreq.ifr_name.as_ptr() as i64, IFNAMSIZ as i64)
+                    setsockopt(self.fd.as_raw_fd(), SOL_SOCKET, SO_BINDTODEVICE, ifreq.ifr_name.as_ptr() as i64, IFNAMSIZ as i64)
                 }
             }
             None => {
                 unsafe {
-                    setsockopt(self.fd, SOL_SOCKET, SO_BINDTODEVICE, 0, 0)
+                    setsockopt(self.fd.as_raw_fd(), SOL_SOCKET, SO_BINDTODEVICE, 0, 0)
                 }
             }
-        } < 0 {
-            unsafe { close(self.fd) };
-            return Err(io::Error::last_os_error());
+        };
+
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
         }
 
         Ok(())
     }
 
+    pub fn try_clone(&self) -> io::Result<Self> {
+        let fd = unsafe { dup(self.fd.as_raw_fd()) };
+        if fd < 0 {
+            return Err(io::Error::from_raw_os_error(-fd as i32));
+        }
+
+        //DUPLICATE SHARES THE SOCKET, SO RING, COUNTERS AND BREAK LOOP ARE SHARED TOO
+        Ok(Self {
+            fd: unsafe { OwnedFd::from_raw_fd(fd as RawFd) },
+            device: self.device.clone(),
+            netns: self.netns.clone(),
+            ring: self.ring.clone(),
+            buffer: Mutex::new(Vec::new()),
+            promiscuous: self.promiscuous,
+            all_multicast: self.all_multicast,
+            timestamp_source: self.timestamp_source,
+            stats: self.stats.clone(),
+            snaplen: self.snaplen,
+            cooked: self.cooked,
+            tun: self.tun,
+            direction: self.direction,
+            timeout: self.timeout,
+            break_loop: self.break_loop.clone()
+        })
+    }
+
     pub fn set_immediate_mode(&self, immediate: bool) ->  io::Result<()> {
         Ok(())
     }
 
+    pub fn set_snaplen(&mut self, snaplen: u32) -> io::Result<()> {
+        if snaplen == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Snapshot length must be greater than 0"));
+        }
+
+        self.snaplen = snaplen;
+        Ok(())
+    }
+
+    pub fn get_snaplen(&self) -> u32 {
+        self.snaplen
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        //ZERO BLOCKS FOREVER, SAME AS PCAP
+        self.timeout = if timeout.is_zero() { None } else { Some(timeout) };
+    }
+
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn get_break_loop(&self) -> BreakLoop {
+        self.break_loop.clone()
+    }
+
+    pub fn set_direction(&mut self, direction: CaptureDirections) -> io::Result<()> {
+        if self.tun.is_none() {
+            let ignore_outgoing = (direction == CaptureDirections::In) as i32;
+            let res = unsafe { setsockopt(self.fd.as_raw_fd(), SOL_PACKET, PACKET_IGNORE_OUTGOING, &ignore_outgoing as *const _ as i64, mem::size_of::<i32>() as i64) };
+            if res < 0 {
+                return Err(io::Error::from_raw_os_error(-res as i32));
+            }
+        }
+
+        self.direction = direction;
+        Ok(())
+    }
+
+    pub fn get_direction(&self) -> CaptureDirections {
+        self.direction
+    }
+
+    pub fn set_rx_ring(&mut self, config: RxRingConfig) -> io::Result<()> {
+        if self.ring.is_some() {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, "RX ring is already configured"));
+        }
+
+        self.ring = Some(Arc::new(Mutex::new(RxRing::new(self.fd.as_raw_fd(), &config)?)));
+        Ok(())
+    }
+
+    pub fn set_promiscuous(&mut self, promiscuous: bool) -> io::Result<()> {
+        if self.promiscuous != promiscuous {
+            self.set_membership(PACKET_MR_PROMISC, promiscuous)?;
+            self.promiscuous = promiscuous;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_promiscuous(&self) -> bool {
+        self.promiscuous
+    }
+
+    pub fn set_all_multicast(&mut self, all_multicast: bool) -> io::Result<()> {
+        if self.all_multicast != all_multicast {
+            self.set_membership(PACKET_MR_ALLMULTI, all_multicast)?;
+            self.all_multicast = all_multicast;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_all_multicast(&self) -> bool {
+        self.all_multicast
+    }
+
+    fn set_membership(&self, _type: u16, enabled: bool) -> io::Result<()> {
+        let device = self.device.as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Membership requires a capture bound to a device"))?;
+
+        let mreq = PacketMreq {
+            mr_ifindex: device.get_index(),
+            mr_type: _type,
+            mr_alen: 0,
+            mr_address: [0; 8]
+        };
+
+        let option = if enabled { PACKET_ADD_MEMBERSHIP } else { PACKET_DROP_MEMBERSHIP };
+        let res = unsafe { setsockopt(self.fd.as_raw_fd(), SOL_PACKET, option, &mreq as *const _ as i64, mem::size_of::<PacketMreq>() as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        Ok(())
+    }
+
+    pub fn set_timestamp_source(&mut self, timestamp_source: TimestampSources) -> io::Result<()> {
+        self.apply_timestamp_source(timestamp_source)?;
+        self.timestamp_source = timestamp_source;
+        Ok(())
+    }
+
+    pub fn get_timestamp_source(&self) -> TimestampSources {
+        self.timestamp_source
+    }
+
+    fn apply_timestamp_source(&self, timestamp_source: TimestampSources) -> io::Result<()> {
+        let (timestampns, timestamping, packet_timestamp) = match timestamp_source {
+            TimestampSources::User => (0i32, 0u32, 0u32),
+            TimestampSources::Software => (1, 0, 0),
+            TimestampSources::Hardware => {
+                self.enable_hardware_timestamps()?;
+                (0, SOF_TIMESTAMPING_RX_HARDWARE | SOF_TIMESTAMPING_RAW_HARDWARE | SOF_TIMESTAMPING_RX_SOFTWARE | SOF_TIMESTAMPING_SOFTWARE, SOF_TIMESTAMPING_RAW_HARDWARE)
+            }
+        };
+
+        for (level, option, value) in [
+            (SOL_SOCKET, SO_TIMESTAMPNS, timestampns as u32),
+            (SOL_SOCKET, SO_TIMESTAMPING, timestamping),
+            (SOL_PACKET, PACKET_TIMESTAMP, packet_timestamp)
+        ] {
+            let res = unsafe { setsockopt(self.fd.as_raw_fd(), level, option, &value as *const _ as i64, mem::size_of::<u32>() as i64) };
+            if res < 0 {
+                return Err(io::Error::from_raw_os_error(-res as i32));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn enable_auxdata(&self) -> io::Result<()> {
+        let auxdata: i32 = 1;
+        let res = unsafe { setsockopt(self.fd.as_raw_fd(), SOL_PACKET, PACKET_AUXDATA, &auxdata as *const _ as i64, mem::size_of::<i32>() as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        Ok(())
+    }
+
+    fn enable_hardware_timestamps(&self) -> io::Result<()> {
+        let device = self.device.as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Hardware timestamps require a capture bound to a device"))?;
+
+        let if_name_bytes = device.get_name().into_bytes();
+        if if_name_bytes.len() >= IFNAMSIZ {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Interface name too long"));
+        }
+
+        let mut config = HwtstampConfig {
+            flags: 0,
+            tx_type: HWTSTAMP_TX_OFF,
+            rx_filter: HWTSTAMP_FILTER_ALL
+        };
+
+        let mut ifreq = IfreqData {
+            ifr_name: [0; IFNAMSIZ],
+            ifr_data: &mut config,
+            ifr_pad: [0; 16]
+        };
+        ifreq.ifr_name[..if_name_bytes.len()].copy_from_slice(&if_name_bytes);
+
+        let res = unsafe { ioctl(self.fd.as_raw_fd(), SIOCSHWTSTAMP as i64, &mut ifreq as *mut _ as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        Ok(())
+    }
+
+    pub fn set_fanout(&self, group_id: u16, mode: FanoutModes, defrag: bool) -> io::Result<()> {
+        let mut _type = mode.get_code();
+        if defrag {
+            _type |= PACKET_FANOUT_FLAG_DEFRAG;
+        }
+
+        let fanout = group_id as u32 | ((_type as u32) << 16);
+        let res = unsafe { setsockopt(self.fd.as_raw_fd(), SOL_PACKET, PACKET_FANOUT, &fanout as *const _ as i64, mem::size_of::<u32>() as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        Ok(())
+    }
+
+    pub fn spawn_fanout<F, C>(device: &Device, group_id: u16, mode: FanoutModes, defrag: bool, workers: usize, factory: F) -> io::Result<Vec<(JoinHandle<io::Result<()>>, BreakLoop)>>
+    where
+        F: Fn(usize) -> C,
+        C: FnMut(SockAddrIn, Packet) -> bool + Send + 'static
+    {
+        let mut captures = Vec::with_capacity(workers);
+
+        for _ in 0..workers {
+            let mut builder = CaptureBuilder::from_device(device);
+            builder.set_fanout(group_id, mode, defrag);
+            captures.push(builder.open()?);
+        }
+
+        Ok(captures.into_iter().enumerate().map(|(i, capture)| {
+            let mut callback = factory(i);
+            let break_loop = capture.get_break_loop();
+
+            let handle = thread::spawn(move || {
+                loop {
+                    match capture.recv() {
+                        Ok((sockaddr, packet)) => {
+                            if !callback(sockaddr, packet) {
+                                break Ok(());
+                            }
+                        }
+                        Err(e) if BreakLoop::is_break(&e) => break Ok(()),
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted || e.kind() == io::ErrorKind::InvalidData => {}
+                        Err(e) => break Err(e)
+                    }
+                }
+            });
+
+            (handle, break_loop)
+        }).collect())
+    }
+
+    pub fn set_filter(&self, filter: &[SockFilter]) -> io::Result<()> {
+        if filter.is_empty() || filter.len() > u16::MAX as usize {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid filter length"));
+        }
+
+        let fprog = SockFprog {
+            len: filter.len() as u16,
+            filter: filter.as_ptr()
+        };
+
+        let res = match self.tun {
+            Some(TunModes::Tun) => return Err(io::Error::new(io::ErrorKind::Unsupported, "Kernel filters can only be attached to TAP devices")),
+            Some(TunModes::Tap) => unsafe { ioctl(self.fd.as_raw_fd(), TUNATTACHFILTER, &fprog as *const _ as i64) },
+            None => unsafe { setsockopt(self.fd.as_raw_fd(), SOL_SOCKET, SO_ATTACH_FILTER, &fprog as *const _ as i64, mem::size_of::<SockFprog>() as i64) }
+        };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_filter(&self) -> io::Result<()> {
+        let detach: i32 = 0;
+        let res = match self.tun {
+            Some(TunModes::Tun) => return Err(io::Error::new(io::ErrorKind::Unsupported, "Kernel filters can only be attached to TAP devices")),
+            Some(TunModes::Tap) => unsafe { ioctl(self.fd.as_raw_fd(), TUNDETACHFILTER, 0) },
+            None => unsafe { setsockopt(self.fd.as_raw_fd(), SOL_SOCKET, SO_DETACH_FILTER, &detach as *const _ as i64, mem::size_of::<i32>() as i64) }
+        };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        Ok(())
+    }
+
+    pub fn lock_filter(&self) -> io::Result<()> {
+        let lock: i32 = 1;
+        let res = unsafe { setsockopt(self.fd.as_raw_fd(), SOL_SOCKET, SO_LOCK_FILTER, &lock as *const _ as i64, mem::size_of::<i32>() as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        Ok(())
+    }
+
+    pub fn stats(&self) -> io::Result<CaptureStats> {
+        if self.tun.is_some() {
+            return self.tun_stats();
+        }
+
+        let mut kernel = TpacketStatsV3 {
+            tp_packets: 0,
+            tp_drops: 0,
+            tp_freeze_q_cnt: 0
+        };
+        let mut length = mem::size_of::<TpacketStatsV3>() as u32;
+
+        let res = unsafe { getsockopt(self.fd.as_raw_fd(), SOL_PACKET, PACKET_STATISTICS, &mut kernel as *mut _ as i64, &mut length as *mut _ as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        //KERNEL COUNTERS RESET ON EVERY READ
+        let mut stats = self.stats.lock().map_err(|_| io::Error::other("Stats lock poisoned"))?;
+        let received = stats.get_received() + kernel.tp_packets as u64;
+        let dropped = stats.get_dropped() + kernel.tp_drops as u64;
+        let freeze_queue_count = stats.get_freeze_queue_count() + kernel.tp_freeze_q_cnt as u64;
+        *stats = CaptureStats::new(received, dropped, freeze_queue_count, 0, 0, 0, 0);
+
+        let names = match self.device {
+            //SYSFS SHOWS OUR OWN NAMESPACE, ITS COUNTERS WOULD BELONG TO SOME OTHER DEVICE
+            _ if self.netns.is_some() => Vec::new(),
+            Some(ref device) => vec![device.get_name()],
+            None => fs::read_dir("/sys/class/net")?
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        };
+
+        let mut interface = [0u64; 4];
+        for name in names {
+            for (i, counter) in ["rx_packets", "rx_dropped", "rx_errors", "rx_missed_errors"].iter().enumerate() {
+                interface[i] += read_counter(&name, counter);
+            }
+        }
+
+        Ok(CaptureStats::new(received, dropped, freeze_queue_count, interface[0], interface[1], interface[2], interface[3]))
+    }
+
+    fn tun_stats(&self) -> io::Result<CaptureStats> {
+        //NO SOCKET QUEUE IN BETWEEN, WHAT THE DEVICE TRANSMITS IS WHAT WE READ
+        let name = match self.device {
+            Some(ref device) if self.netns.is_none() => device.get_name(),
+            _ => return Ok(CaptureStats::default())
+        };
+
+        Ok(CaptureStats::new(read_counter(&name, "tx_packets"), read_counter(&name, "tx_dropped"), 0,
+            read_counter(&name, "rx_packets"), read_counter(&name, "rx_dropped"), read_counter(&name, "rx_errors"), read_counter(&name, "rx_missed_errors")))
+    }
+
     pub fn send(&self, packet: Packet) -> io::Result<usize> {
+        match self.device {
+            Some(ref device) => self.send_to_with_flags(packet, device, 0),
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, "Capture is not bound to a device, use send_to"))
+        }
+    }
+
+    pub fn try_send(&self, packet: Packet) -> io::Result<usize> {
+        match self.device {
+            Some(ref device) => self.send_to_with_flags(packet, device, MSG_DONTWAIT),
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, "Capture is not bound to a device, use try_send_to"))
+        }
+    }
+
+    pub fn send_to(&self, packet: Packet, device: &Device) -> io::Result<usize> {
+        self.send_to_with_flags(packet, device, 0)
+    }
+
+    pub fn try_send_to(&self, packet: Packet, device: &Device) -> io::Result<usize> {
+        self.send_to_with_flags(packet, device, MSG_DONTWAIT)
+    }
+
+    fn send_to_with_flags(&self, packet: Packet, device: &Device, flags: i64) -> io::Result<usize> {
+        if self.cooked {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "Cooked captures can't send link layer frames"));
+        }
+
+        if let Some(mode) = self.tun {
+            if self.device.as_ref().map(Device::get_index) != Some(device.get_index()) {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "TUN/TAP captures can only send through their own device"));
+            }
+
+            let expected = match mode {
+                TunModes::Tun => DataLinkTypes::Raw,
+                TunModes::Tap => DataLinkTypes::En10mb
+            };
+
+            if packet.get_data_link_type() != expected {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("{} devices only take {} frames", mode.to_string(), expected.to_string())));
+            }
+        }
+
         let mut packet = packet.to_bytes();
 
-        let len = unsafe { sendto(self.fd, &mut packet) };
+        let sockaddr = SockAddrIn {
+            sll_family: AF_PACKET as u16,
+            sll_protocol: ETH_P_ALL.to_be(),
+            sll_ifindex: device.get_index(),
+            sll_hatype: 0,
+            sll_pkttype: 0,
+            sll_halen: 0,
+            sll_addr: [0; 8]
+        };
+
+        loop {
+            let len = match self.tun {
+                //EVERY WRITE IS ONE FRAME INJECTED AS IF THE DEVICE RECEIVED IT
+                Some(_) => unsafe { write(self.fd.as_raw_fd(), &packet) },
+                None => unsafe { sendto(self.fd.as_raw_fd(), &mut packet, flags, Some(&sockaddr)) }
+            };
+            if len >= 0 {
+                return Ok(len as usize);
+            }
 
-        if len > 0 {
-            Ok(len as usize)
-        } else {
-            Err(io::Error::last_os_error())
+            //SOCKET IS NON-BLOCKING, ONLY WAIT WHEN THE CALLER ASKED TO BLOCK
+            if -len as i32 == EAGAIN && flags & MSG_DONTWAIT == 0 {
+                self.wait(POLLOUT, None)?;
+                continue;
+            }
+
+            return Err(io::Error::from_raw_os_error(-len as i32));
         }
     }
 
     pub fn recv(&self) -> io::Result<(SockAddrIn, Packet)> {
-        self.recv_with_flags(0)
+        self.recv_with_flags(0, self.timeout)
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> io::Result<(SockAddrIn, Packet)> {
+        self.recv_with_flags(0, Some(timeout))
     }
 
     pub fn try_recv(&self) -> io::Result<(SockAddrIn, Packet)> {
-        self.recv_with_flags(MSG_DONTWAIT)
+        self.recv_with_flags(MSG_DONTWAIT, None)
     }
 
-    fn recv_with_flags(&self, flags: i64) -> io::Result<(SockAddrIn, Packet)> {
-        let mut buffer = vec![0u8; 4096];
-        let mut sockaddr: SockAddrIn = SockAddrIn {
-            sll_family: 0,
+    fn recv_with_flags(&self, flags: i64, timeout: Option<Duration>) -> io::Result<(SockAddrIn, Packet)> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        if flags & MSG_DONTWAIT == 0 {
+            self.break_loop.check()?;
+        }
+
+        if let Some(ref ring) = self.ring {
+            return self.recv_from_ring(ring, flags, deadline);
+        }
+
+        if self.tun.is_some() {
+            return self.recv_from_tun(flags, deadline);
+        }
+
+        let snaplen = self.snaplen as usize;
+        let mut buffer = self.lock_buffer(snaplen)?;
+
+        loop {
+            let mut sockaddr: SockAddrIn = SockAddrIn {
+                sll_family: 0,
+                sll_protocol: 0,
+                sll_ifindex: 0,
+                sll_hatype: 0,
+                sll_pkttype: 0,
+                sll_halen: 0,
+                sll_addr: [0; 8]
+            };
+
+            let mut control = [0u8; 128];
+            let mut iov = IoVec {
+                iov_base: buffer.as_mut_ptr(),
+                iov_len: snaplen
+            };
+
+            let mut msg = MsgHdr {
+                msg_name: &mut sockaddr,
+                msg_namelen: mem::size_of::<SockAddrIn>() as u32,
+                msg_iov: &mut iov,
+                msg_iovlen: 1,
+                msg_control: control.as_mut_ptr(),
+                msg_controllen: control.len(),
+                msg_flags: 0
+            };
+
+            let len = unsafe { recvmsg(self.fd.as_raw_fd(), &mut msg, flags | MSG_TRUNC) };
+
+            if len > 0 {
+                if !self.accepts(&sockaddr) {
+                    continue;
+                }
+
+                let (timestamp, auxdata) = parse_control(&control[..msg.msg_controllen.min(control.len())]);
+                let (frame_time, timestamp_source) = timestamp.unwrap_or_else(user_timestamp);
+
+                return Self::to_packet(self.cooked, sockaddr, frame_time, timestamp_source, len as usize, auxdata, &buffer[..(len as usize).min(snaplen)]);
+
+            } else if len < 0 {
+                if -len as i32 == EAGAIN && flags & MSG_DONTWAIT == 0 {
+                    self.wait(POLLIN, deadline)?;
+                    continue;
+                }
+
+                return Err(io::Error::from_raw_os_error(-len as i32));
+            }
+
+            return Err(io::Error::new(io::ErrorKind::WouldBlock, "No data available"));
+        }
+    }
+
+    fn recv_from_ring(&self, ring: &Mutex<RxRing>, flags: i64, deadline: Option<Instant>) -> io::Result<(SockAddrIn, Packet)> {
+        let mut ring = ring.lock().map_err(|_| io::Error::other("RX ring lock poisoned"))?;
+
+        loop {
+            if let Some((sockaddr, frame_time, timestamp_source, auxdata, data)) = ring.next() {
+                if !self.accepts(&sockaddr) {
+                    continue;
+                }
+
+                let data = &data[..data.len().min(self.snaplen as usize)];
+                return Self::to_packet(self.cooked, sockaddr, frame_time, timestamp_source, auxdata.tp_len as usize, Some(auxdata), data);
+            }
+
+            if flags & MSG_DONTWAIT != 0 {
+                return Err(io::Error::new(io::ErrorKind::WouldBlock, "No data available"));
+            }
+
+            self.wait(POLLIN, deadline)?;
+        }
+    }
+
+    fn recv_from_tun(&self, flags: i64, deadline: Option<Instant>) -> io::Result<(SockAddrIn, Packet)> {
+        let mut buffer = self.lock_buffer(TUN_BUFFER_LEN)?;
+
+        loop {
+            let len = unsafe { read(self.fd.as_raw_fd(), &mut buffer[..TUN_BUFFER_LEN]) };
+
+            if len > 0 {
+                let data = &buffer[..len as usize];
+                let sockaddr = self.tun_sockaddr(data);
+                if !self.accepts(&sockaddr) {
+                    continue;
+                }
+
+                let (frame_time, timestamp_source) = user_timestamp();
+                return Self::to_packet(false, sockaddr, frame_time, timestamp_source, data.len(), None, &data[..data.len().min(self.snaplen as usize)]);
+
+            } else if len < 0 {
+                if -len as i32 == EAGAIN && flags & MSG_DONTWAIT == 0 {
+                    self.wait(POLLIN, deadline)?;
+                    continue;
+                }
+
+                return Err(io::Error::from_raw_os_error(-len as i32));
+            }
+
+            return Err(io::Error::new(io::ErrorKind::WouldBlock, "No data available"));
+        }
+    }
+
+    fn lock_buffer(&self, len: usize) -> io::Result<MutexGuard<'_, Vec<u8>>> {
+        let mut buffer = self.buffer.lock().map_err(|_| io::Error::other("Receive buffer lock poisoned"))?;
+
+        //GROWN ONCE AND REUSED, EVERY READ ONLY LOOKS AT THE FIRST LEN BYTES
+        if buffer.len() < len {
+            buffer.resize(len, 0);
+        }
+
+        Ok(buffer)
+    }
+
+    fn tun_sockaddr(&self, data: &[u8]) -> SockAddrIn {
+        let mut sockaddr = SockAddrIn {
+            sll_family: AF_PACKET as u16,
             sll_protocol: 0,
-            sll_ifindex: 0,
-            sll_hatype: 0,
-            sll_pkttype: 0,
+            sll_ifindex: self.device.as_ref().map(Device::get_index).unwrap_or(0),
+            sll_hatype: ARPHRD_NONE,
+            sll_pkttype: PacketTypes::OutGoing.get_code(),
             sll_halen: 0,
             sll_addr: [0; 8]
         };
 
-        let len = unsafe { recvfrom(self.fd, &mut buffer, flags, &mut sockaddr) };
+        match self.tun {
+            Some(TunModes::Tap) => {
+                sockaddr.sll_hatype = ARPHRD_ETHER;
+                if data.len() >= 14 {
+                    sockaddr.sll_protocol = u16::from_be_bytes([data[12], data[13]]).to_be();
+                    sockaddr.sll_halen = 6;
+                    sockaddr.sll_addr[..6].copy_from_slice(&data[6..12]);
+                }
+            }
+            _ => {
+                sockaddr.sll_protocol = match data.first().map(|b| b >> 4) {
+                    Some(4) => EthernetTypes::Ipv4.get_code().to_be(),
+                    Some(6) => EthernetTypes::Ipv6.get_code().to_be(),
+                    _ => 0
+                };
+            }
+        }
+
+        sockaddr
+    }
+
+    pub fn recv_batch(&self, bufs: &mut [PacketBuf]) -> io::Result<usize> {
+        self.recv_batch_with_flags(bufs, MSG_WAITFORONE, self.timeout)
+    }
+
+    pub fn try_recv_batch(&self, bufs: &mut [PacketBuf]) -> io::Result<usize> {
+        self.recv_batch_with_flags(bufs, MSG_DONTWAIT, None)
+    }
+
+    fn recv_batch_with_flags(&self, bufs: &mut [PacketBuf], flags: i64, timeout: Option<Duration>) -> io::Result<usize> {
+        if bufs.is_empty() {
+            return Ok(0);
+        }
+
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        if flags & MSG_DONTWAIT == 0 {
+            self.break_loop.check()?;
+        }
+
+        if let Some(ref ring) = self.ring {
+            return self.recv_batch_from_ring(ring, bufs, flags, deadline);
+        }
+
+        if self.tun.is_some() {
+            return self.recv_batch_from_tun(bufs, flags, deadline);
+        }
+
+        let snaplen = self.snaplen as usize;
+
+        loop {
+            let mut iovs: Vec<IoVec> = bufs.iter_mut().map(|buf| IoVec {
+                iov_base: buf.buffer.as_mut_ptr(),
+                iov_len: buf.buffer.len().min(snaplen)
+            }).collect();
+
+            let mut msgs: Vec<MmsgHdr> = bufs.iter_mut().zip(iovs.iter_mut()).map(|(buf, iov)| MmsgHdr {
+                msg_hdr: MsgHdr {
+                    msg_name: &mut buf.sockaddr,
+                    msg_namelen: mem::size_of::<SockAddrIn>() as u32,
+                    msg_iov: iov,
+                    msg_iovlen: 1,
+                    msg_control: buf.control.as_mut_ptr(),
+                    msg_controllen: buf.control.len(),
+                    msg_flags: 0
+                },
+                msg_len: 0
+            }).collect();
+
+            let count = unsafe { recvmmsg(self.fd.as_raw_fd(), &mut msgs, flags | MSG_TRUNC) };
+            if count < 0 && -count as i32 == EAGAIN && flags & MSG_DONTWAIT == 0 {
+                self.wait(POLLIN, deadline)?;
+                continue;
+
+            } else if count < 0 {
+                return Err(io::Error::from_raw_os_error(-count as i32));
+            }
+
+            let mut filled = 0;
+            for (i, msg) in msgs.iter().enumerate().take(count as usize) {
+                if !self.accepts(&bufs[i].sockaddr) {
+                    continue;
+                }
+
+                let buf = &mut bufs[i];
+                let (timestamp, auxdata) = parse_control(&buf.control[..msg.msg_hdr.msg_controllen.min(buf.control.len())]);
+                (buf.frame_time, buf.timestamp_source) = timestamp.unwrap_or_else(user_timestamp);
+                buf.auxdata = auxdata;
+                buf.cooked = self.cooked;
+                buf.original_length = msg.msg_len as usize;
+                buf.length = buf.original_length.min(buf.buffer.len()).min(snaplen);
+
+                bufs.swap(filled, i);
+                filled += 1;
+            }
+
+            if filled > 0 || count == 0 {
+                return Ok(filled);
+            }
+        }
+    }
+
+    fn recv_batch_from_ring(&self, ring: &Mutex<RxRing>, bufs: &mut [PacketBuf], flags: i64, deadline: Option<Instant>) -> io::Result<usize> {
+        let mut ring = ring.lock().map_err(|_| io::Error::other("RX ring lock poisoned"))?;
+        let snaplen = self.snaplen as usize;
+        let mut filled = 0;
+
+        loop {
+            while filled < bufs.len() {
+                let Some((sockaddr, frame_time, timestamp_source, auxdata, data)) = ring.next() else {
+                    break;
+                };
+
+                if !self.accepts(&sockaddr) {
+                    continue;
+                }
+
+                let buf = &mut bufs[filled];
+                let length = data.len().min(buf.buffer.len()).min(snaplen);
+                buf.buffer[..length].copy_from_slice(&data[..length]);
+                buf.length = length;
+                buf.original_length = auxdata.tp_len as usize;
+                buf.sockaddr = sockaddr;
+                buf.frame_time = frame_time;
+                buf.timestamp_source = timestamp_source;
+                buf.auxdata = Some(auxdata);
+                buf.cooked = self.cooked;
+                filled += 1;
+            }
+
+            if filled > 0 {
+                return Ok(filled);
+            }
+
+            if flags & MSG_DONTWAIT != 0 {
+                return Err(io::Error::new(io::ErrorKind::WouldBlock, "No data available"));
+            }
+
+            self.wait(POLLIN, deadline)?;
+        }
+    }
+
+    fn recv_batch_from_tun(&self, bufs: &mut [PacketBuf], flags: i64, deadline: Option<Instant>) -> io::Result<usize> {
+        let snaplen = self.snaplen as usize;
+        let mut filled = 0;
+
+        loop {
+            //ONE READ PER FRAME, KEEP GOING UNTIL THE DEVICE HAS NOTHING LEFT
+            while filled < bufs.len() {
+                let buf = &mut bufs[filled];
+                let len = unsafe { read(self.fd.as_raw_fd(), &mut buf.buffer) };
+                if len < 0 && -len as i32 == EAGAIN {
+                    break;
+
+                } else if len < 0 {
+                    return Err(io::Error::from_raw_os_error(-len as i32));
+                }
+
+                let sockaddr = self.tun_sockaddr(&buf.buffer[..len as usize]);
+                if !self.accepts(&sockaddr) {
+                    continue;
+                }
+
+                buf.length = (len as usize).min(snaplen);
+                buf.original_length = len as usize;
+                buf.sockaddr = sockaddr;
+                (buf.frame_time, buf.timestamp_source) = user_timestamp();
+                buf.auxdata = None;
+                buf.cooked = false;
+                filled += 1;
+            }
+
+            if filled > 0 {
+                return Ok(filled);
+            }
+
+            if flags & MSG_DONTWAIT != 0 {
+                return Err(io::Error::new(io::ErrorKind::WouldBlock, "No data available"));
+            }
+
+            self.wait(POLLIN, deadline)?;
+        }
+    }
+
+    fn wait(&self, events: i16, deadline: Option<Instant>) -> io::Result<()> {
+        let timeout = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Err(io::Error::new(io::ErrorKind::TimedOut, "Timed out waiting for packets"));
+                }
+
+                remaining.as_nanos().div_ceil(1_000_000).min(i32::MAX as u128) as i32
+            }
+            None => -1
+        };
+
+        let mut fds = [
+            PollFd {
+                fd: self.fd.as_raw_fd(),
+                events: events | POLLERR,
+                revents: 0
+            },
+            PollFd {
+                fd: self.break_loop.get_fd(),
+                events: POLLIN,
+                revents: 0
+            }
+        ];
+
+        let res = unsafe { poll(&mut fds, timeout) };
+        if res < 0 && -res as i32 != EINTR {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        if res == 0 {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "Timed out waiting for packets"));
+        }
+
+        self.break_loop.check()
+    }
+
+    fn accepts(&self, sockaddr: &SockAddrIn) -> bool {
+        match self.direction {
+            CaptureDirections::In => sockaddr.sll_pkttype != PacketTypes::OutGoing.get_code(),
+            CaptureDirections::Out => sockaddr.sll_pkttype == PacketTypes::OutGoing.get_code(),
+            CaptureDirections::InOut => true
+        }
+    }
+
+    pub(crate) fn to_packet(cooked: bool, sockaddr: SockAddrIn, frame_time: u128, timestamp_source: TimestampSources, original_length: usize, auxdata: Option<TpacketAuxdata>, data: &[u8]) -> io::Result<(SockAddrIn, Packet)> {
+        let data_link_type = if cooked {
+            DataLinkTypes::Sll2
+        } else {
+            DataLinkTypes::from_sll2_code(sockaddr.sll_hatype)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+        };
+
+        let (mut packet, original_length) = match auxdata {
+            //SAME HEADER LIBPCAP WRITES FOR LINKTYPE_LINUX_SLL2, THE VLAN TAG HAS NOWHERE TO GO SO IT IS DROPPED LIKE THERE
+            _ if cooked => {
+                let mut frame = Vec::with_capacity(SLL2_FRAME_LEN + data.len());
+                frame.extend_from_slice(&sockaddr.sll_protocol.to_ne_bytes());
+                frame.extend_from_slice(&[0; 2]);
+                frame.extend_from_slice(&sockaddr.sll_ifindex.to_be_bytes());
+                frame.extend_from_slice(&sockaddr.sll_hatype.to_be_bytes());
+                frame.push(sockaddr.sll_pkttype);
+                frame.push(sockaddr.sll_halen);
+                frame.extend_from_slice(&sockaddr.sll_addr);
+                frame.extend_from_slice(data);
 
-        if len > 0 {
-            let now = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("Time went backwards")
-                .as_millis();
+                (Packet::new(data_link_type, frame_time, &frame)?, original_length + SLL2_FRAME_LEN)
+            }
+            //KERNEL STRIPS THE 802.1Q TAG, PUT IT BACK WHERE IT WAS ON THE WIRE
+            Some(ref auxdata) if data_link_type == DataLinkTypes::En10mb && data.len() >= 12
+                    && (auxdata.tp_vlan_tci != 0 || auxdata.tp_status & TP_STATUS_VLAN_VALID != 0) => {
+                let tpid = if auxdata.tp_status & TP_STATUS_VLAN_TPID_VALID != 0 {
+                    auxdata.tp_vlan_tpid
+                } else {
+                    EthernetTypes::Vlan.get_code()
+                };
 
-            let data_link_type = DataLinkTypes::from_sll2_code(sockaddr.sll_hatype)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+                let mut tagged = Vec::with_capacity(data.len() + 4);
+                tagged.extend_from_slice(&data[..12]);
+                tagged.extend_from_slice(&tpid.to_be_bytes());
+                tagged.extend_from_slice(&auxdata.tp_vlan_tci.to_be_bytes());
+                tagged.extend_from_slice(&data[12..]);
 
-            return Ok((sockaddr, Packet::new(data_link_type, now, &buffer[..len as usize])));
+                (Packet::new(data_link_type, frame_time, &tagged)?, original_length + 4)
+            }
+            _ => (Packet::new(data_link_type, frame_time, data)?, original_length)
+        };
 
-        } else if len == -1 {
-            return Err(io::Error::last_os_error());
+        if let Some(ref auxdata) = auxdata {
+            packet.set_checksum_status(if auxdata.tp_status & TP_STATUS_CSUMNOTREADY != 0 {
+                ChecksumStatuses::NotReady
+            } else if auxdata.tp_status & TP_STATUS_CSUM_VALID != 0 {
+                ChecksumStatuses::Valid
+            } else {
+                ChecksumStatuses::Unknown
+            });
         }
 
-        Err(io::Error::new(io::ErrorKind::WouldBlock, "No data available"))
+        packet.set_timestamp_source(timestamp_source);
+        packet.set_original_length(original_length);
+        packet.set_packet_type(PacketTypes::from_code(sockaddr.sll_pkttype).ok());
+        Ok((sockaddr, packet))
     }
 
-    pub fn close(&self) {
-        unsafe { close(self.fd) };
+    pub fn close(self) {
+        drop(self);
     }
 
     pub fn get_device(&self) -> Option<&Device> {
         self.device.as_ref()
     }
+
+    pub fn is_cooked(&self) -> bool {
+        self.cooked
+    }
+
+    pub fn get_tun_mode(&self) -> Option<TunModes> {
+        self.tun
+    }
+
+    pub fn get_netns(&self) -> Option<&NetNamespaces> {
+        self.netns.as_ref()
+    }
+}
+
+impl CaptureSource for Capture {
+
+    fn open(&mut self) -> io::Result<()> {
+        //CAPTUREBUILDER::OPEN ALREADY DID THE WORK
+        Ok(())
+    }
+
+    fn recv(&self) -> io::Result<Packet> {
+        Capture::recv(self).map(|(_, packet)| packet)
+    }
+
+    fn try_recv(&self) -> io::Result<Packet> {
+        Capture::try_recv(self).map(|(_, packet)| packet)
+    }
+
+    fn send(&self, packet: Packet) -> io::Result<usize> {
+        Capture::send(self, packet)
+    }
+
+    fn stats(&self) -> io::Result<CaptureStats> {
+        Capture::stats(self)
+    }
+
+    fn set_filter(&self, filter: &[SockFilter]) -> io::Result<()> {
+        Capture::set_filter(self, filter)
+    }
+
+    fn get_data_link_type(&self) -> DataLinkTypes {
+        match self.tun {
+            Some(TunModes::Tun) => DataLinkTypes::Raw,
+            Some(TunModes::Tap) => DataLinkTypes::En10mb,
+            None if self.cooked => DataLinkTypes::Sll2,
+            //ANY WITHOUT COOKED MODE MIXES LINK TYPES, EACH PACKET CARRIES ITS OWN
+            None => self.device.as_ref()
+                .map(Device::get_data_link_type)
+                .unwrap_or(DataLinkTypes::Null)
+        }
+    }
+}
+
+impl AsRawFd for Capture {
+
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+impl AsFd for Capture {
+
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.as_fd()
+    }
+}
+
+fn parse_control(control: &[u8]) -> (Option<(u128, TimestampSources)>, Option<TpacketAuxdata>) {
+    let header_length = mem::size_of::<CmsgHdr>();
+    let mut timestamp = None;
+    let mut auxdata = None;
+    let mut offset = 0;
+
+    while offset + header_length <= control.len() {
+        let cmsg = unsafe { ptr::read_unaligned(control.as_ptr().add(offset) as *const CmsgHdr) };
+        if cmsg.cmsg_len < header_length || offset + cmsg.cmsg_len > control.len() {
+            break;
+        }
+
+        let data = &control[offset + header_length..offset + cmsg.cmsg_len];
+
+        match (cmsg.cmsg_level as i64, cmsg.cmsg_type as i64) {
+            (SOL_SOCKET, SCM_TIMESTAMPNS) if data.len() >= mem::size_of::<TimeSpec>() => {
+                let ts = unsafe { ptr::read_unaligned(data.as_ptr() as *const TimeSpec) };
+                timestamp = Some((to_nanos(&ts), TimestampSources::Software));
+            }
+            (SOL_SOCKET, SCM_TIMESTAMPING) if data.len() >= mem::size_of::<[TimeSpec; 3]>() => {
+                let ts = unsafe { ptr::read_unaligned(data.as_ptr() as *const [TimeSpec; 3]) };
+
+                if ts[2].tv_sec != 0 || ts[2].tv_nsec != 0 {
+                    timestamp = Some((to_nanos(&ts[2]), TimestampSources::Hardware));
+
+                } else if ts[0].tv_sec != 0 || ts[0].tv_nsec != 0 {
+                    timestamp = Some((to_nanos(&ts[0]), TimestampSources::Software));
+                }
+            }
+            (SOL_PACKET, PACKET_AUXDATA) if data.len() >= mem::size_of::<TpacketAuxdata>() => {
+                auxdata = Some(unsafe { ptr::read_unaligned(data.as_ptr() as *const TpacketAuxdata) });
+            }
+            _ => {}
+        }
+
+        offset += (cmsg.cmsg_len + mem::size_of::<usize>() - 1) & !(mem::size_of::<usize>() - 1);
+    }
+
+    (timestamp, auxdata)
+}
+
+fn read_counter(name: &str, counter: &str) -> u64 {
+    fs::read_to_string(format!("/sys/class/net/{}/statistics/{}", name, counter)).ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(0)
+}
+
+fn user_timestamp() -> (u128, TimestampSources) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_nanos();
+
+    (now, TimestampSources::User)
+}
+
+fn to_nanos(ts: &TimeSpec) -> u128 {
+    (ts.tv_sec as u128 * 1_000_000_000) + ts.tv_nsec as u128
 }
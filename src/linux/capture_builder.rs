@@ -0,0 +1,166 @@
+use std::io;
+use std::time::Duration;
+use crate::devices::Device;
+use crate::linux::capture::{Capture, DEFAULT_SNAPLEN};
+use crate::linux::fanout_modes::FanoutModes;
+use crate::linux::net_namespaces::NetNamespaces;
+use crate::linux::rx_ring::RxRingConfig;
+use crate::linux::tun_modes::TunModes;
+use crate::utils::capture_directions::CaptureDirections;
+use crate::utils::sock_filter::SockFilter;
+use crate::utils::timestamp_sources::TimestampSources;
+
+#[derive(Debug, Clone)]
+pub struct CaptureBuilder {
+    device: Option<Device>,
+    netns: Option<NetNamespaces>,
+    tun: Option<(String, TunModes)>,
+    snaplen: u32,
+    cooked: bool,
+    promiscuous: bool,
+    all_multicast: bool,
+    timestamp_source: TimestampSources,
+    direction: CaptureDirections,
+    timeout: Option<Duration>,
+    rx_ring: Option<RxRingConfig>,
+    filter: Option<Vec<SockFilter>>,
+    fanout: Option<(u16, FanoutModes, bool)>
+}
+
+impl CaptureBuilder {
+
+    pub fn any() -> Self {
+        Self {
+            device: None,
+            netns: None,
+            tun: None,
+            snaplen: DEFAULT_SNAPLEN,
+            cooked: false,
+            promiscuous: false,
+            all_multicast: false,
+            timestamp_source: TimestampSources::Software,
+            direction: CaptureDirections::InOut,
+            timeout: None,
+            rx_ring: None,
+            filter: None,
+            fanout: None
+        }
+    }
+
+    pub fn from_device(device: &Device) -> Self {
+        let mut builder = Self::any();
+        builder.device = Some(device.clone());
+        builder
+    }
+
+    pub fn tun(name: &str, mode: TunModes) -> Self {
+        let mut builder = Self::any();
+        builder.tun = Some((name.to_string(), mode));
+        builder
+    }
+
+    pub fn open(self) -> io::Result<Capture> {
+        Capture::open(self)
+    }
+
+    pub fn get_device(&self) -> Option<&Device> {
+        self.device.as_ref()
+    }
+
+    pub fn get_tun(&self) -> Option<(&str, TunModes)> {
+        self.tun.as_ref().map(|(name, mode)| (name.as_str(), *mode))
+    }
+
+    pub fn set_netns(&mut self, netns: impl Into<NetNamespaces>) {
+        self.netns = Some(netns.into());
+    }
+
+    pub fn get_netns(&self) -> Option<&NetNamespaces> {
+        self.netns.as_ref()
+    }
+
+    pub fn set_snaplen(&mut self, snaplen: u32) -> io::Result<()> {
+        if snaplen == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Snapshot length must be greater than 0"));
+        }
+
+        self.snaplen = snaplen;
+        Ok(())
+    }
+
+    pub fn get_snaplen(&self) -> u32 {
+        self.snaplen
+    }
+
+    pub fn set_cooked(&mut self, cooked: bool) {
+        self.cooked = cooked;
+    }
+
+    pub fn is_cooked(&self) -> bool {
+        self.cooked
+    }
+
+    pub fn set_promiscuous(&mut self, promiscuous: bool) {
+        self.promiscuous = promiscuous;
+    }
+
+    pub fn is_promiscuous(&self) -> bool {
+        self.promiscuous
+    }
+
+    pub fn set_all_multicast(&mut self, all_multicast: bool) {
+        self.all_multicast = all_multicast;
+    }
+
+    pub fn is_all_multicast(&self) -> bool {
+        self.all_multicast
+    }
+
+    pub fn set_timestamp_source(&mut self, timestamp_source: TimestampSources) {
+        self.timestamp_source = timestamp_source;
+    }
+
+    pub fn get_timestamp_source(&self) -> TimestampSources {
+        self.timestamp_source
+    }
+
+    pub fn set_direction(&mut self, direction: CaptureDirections) {
+        self.direction = direction;
+    }
+
+    pub fn get_direction(&self) -> CaptureDirections {
+        self.direction
+    }
+
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = if timeout.is_zero() { None } else { Some(timeout) };
+    }
+
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn set_rx_ring(&mut self, config: RxRingConfig) {
+        self.rx_ring = Some(config);
+    }
+
+    pub fn get_rx_ring(&self) -> Option<RxRingConfig> {
+        self.rx_ring
+    }
+
+    pub fn set_filter(&mut self, filter: &[SockFilter]) {
+        self.filter = Some(filter.to_vec());
+    }
+
+    pub fn get_filter(&self) -> Option<&[SockFilter]> {
+        self.filter.as_deref()
+    }
+
+    pub fn set_fanout(&mut self, group_id: u16, mode: FanoutModes, defrag: bool) {
+        self.fanout = Some((group_id, mode, defrag));
+    }
+
+    pub fn get_fanout(&self) -> Option<(u16, FanoutModes, bool)> {
+        self.fanout
+    }
+}
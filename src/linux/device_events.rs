@@ -0,0 +1,43 @@
+use crate::devices::Device;
+use crate::utils::device_address::DeviceAddress;
+
+#[derive(Clone, Debug)]
+pub enum DeviceEvents {
+    Added(Device),
+    Removed(Device),
+    Up(Device),
+    Down(Device),
+    Renamed(Device, String),
+    Changed(Device),
+    AddressAdded(Device, DeviceAddress),
+    AddressRemoved(Device, DeviceAddress)
+}
+
+impl DeviceEvents {
+
+    pub fn get_device(&self) -> &Device {
+        match self {
+            Self::Added(device) |
+            Self::Removed(device) |
+            Self::Up(device) |
+            Self::Down(device) |
+            Self::Renamed(device, _) |
+            Self::Changed(device) |
+            Self::AddressAdded(device, _) |
+            Self::AddressRemoved(device, _) => device
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Added(_) => "Added",
+            Self::Removed(_) => "Removed",
+            Self::Up(_) => "Up",
+            Self::Down(_) => "Down",
+            Self::Renamed(_, _) => "Renamed",
+            Self::Changed(_) => "Changed",
+            Self::AddressAdded(_, _) => "Address Added",
+            Self::AddressRemoved(_, _) => "Address Removed"
+        }.to_string()
+    }
+}
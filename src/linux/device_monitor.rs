@@ -0,0 +1,248 @@
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::os::fd::{AsRawFd, RawFd};
+use std::time::{Duration, Instant};
+use crate::devices::{parse_address, Device};
+use crate::linux::device_events::DeviceEvents;
+use crate::linux::netlink::{Netlink, NetlinkMessage};
+use crate::linux::sys::{poll, PollFd, EINTR, ENOBUFS, MSG_DONTWAIT, POLLERR, POLLIN, RTMGRP_IPV4_IFADDR, RTMGRP_IPV6_IFADDR, RTMGRP_LINK, RTM_DELADDR, RTM_DELLINK, RTM_NEWADDR, RTM_NEWLINK};
+
+#[derive(Debug)]
+pub struct DeviceMonitor {
+    netlink: Netlink,
+    devices: HashMap<i32, Device>,
+    events: VecDeque<DeviceEvents>
+}
+
+impl DeviceMonitor {
+
+    pub fn new() -> io::Result<Self> {
+        //SUBSCRIBE BEFORE LISTING SO NO CHANGE FALLS BETWEEN THE TWO
+        let netlink = Netlink::new(RTMGRP_LINK | RTMGRP_IPV4_IFADDR | RTMGRP_IPV6_IFADDR)?;
+
+        let devices = Device::list()?.into_iter()
+            .map(|device| (device.get_index(), device))
+            .collect();
+
+        Ok(Self {
+            netlink,
+            devices,
+            events: VecDeque::new()
+        })
+    }
+
+    pub fn get_devices(&self) -> Vec<Device> {
+        let mut devices: Vec<Device> = self.devices.values().cloned().collect();
+        devices.sort_by_key(Device::get_index);
+        devices
+    }
+
+    pub fn recv(&mut self) -> io::Result<DeviceEvents> {
+        self.recv_with_deadline(None)
+    }
+
+    pub fn recv_timeout(&mut self, timeout: Duration) -> io::Result<DeviceEvents> {
+        self.recv_with_deadline(Some(Instant::now() + timeout))
+    }
+
+    pub fn try_recv(&mut self) -> io::Result<DeviceEvents> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(event);
+            }
+
+            self.read()?;
+        }
+    }
+
+    fn recv_with_deadline(&mut self, deadline: Option<Instant>) -> io::Result<DeviceEvents> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(event);
+            }
+
+            match self.read() {
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => self.wait(deadline)?,
+                Err(e) => return Err(e)
+            }
+        }
+    }
+
+    fn read(&mut self) -> io::Result<()> {
+        let messages = match self.netlink.recv(MSG_DONTWAIT) {
+            Ok(messages) => messages,
+            Err(e) if e.raw_os_error() == Some(ENOBUFS) => return self.resync(),
+            Err(e) => return Err(e)
+        };
+
+        for message in messages {
+            self.handle(message);
+        }
+
+        Ok(())
+    }
+
+    fn handle(&mut self, message: NetlinkMessage) {
+        match message._type {
+            RTM_NEWLINK => {
+                let Some(mut device) = Device::from_link(&message.payload, true) else {
+                    return;
+                };
+
+                if let Some(previous) = self.devices.get(&device.get_index()) {
+                    //LINK MESSAGES CARRY NO ADDRESSES, KEEP THE ONES WE ALREADY KNOW
+                    for address in previous.get_addresses() {
+                        device.add_address(address);
+                    }
+                }
+
+                diff(self.devices.get(&device.get_index()), &device, &mut self.events);
+                self.devices.insert(device.get_index(), device);
+            }
+            RTM_DELLINK => {
+                let Some(device) = Device::from_link(&message.payload, true) else {
+                    return;
+                };
+
+                let device = self.devices.remove(&device.get_index()).unwrap_or(device);
+                self.events.push_back(DeviceEvents::Removed(device));
+            }
+            RTM_NEWADDR => {
+                let Some((index, address)) = parse_address(&message.payload) else {
+                    return;
+                };
+
+                if let Some(device) = self.devices.get_mut(&index) {
+                    if !device.get_addresses().contains(&address) {
+                        device.add_address(address);
+                        self.events.push_back(DeviceEvents::AddressAdded(device.clone(), address));
+                    }
+                }
+            }
+            RTM_DELADDR => {
+                let Some((index, address)) = parse_address(&message.payload) else {
+                    return;
+                };
+
+                if let Some(device) = self.devices.get_mut(&index) {
+                    if device.get_addresses().contains(&address) {
+                        device.remove_address(&address);
+                        self.events.push_back(DeviceEvents::AddressRemoved(device.clone(), address));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn resync(&mut self) -> io::Result<()> {
+        //KERNEL DROPPED EVENTS, DIFF A FRESH LIST AGAINST WHAT WE HAD
+        let devices: HashMap<i32, Device> = Device::list()?.into_iter()
+            .map(|device| (device.get_index(), device))
+            .collect();
+
+        let mut indexes: Vec<i32> = self.devices.keys().chain(devices.keys()).copied().collect();
+        indexes.sort();
+        indexes.dedup();
+
+        for index in indexes {
+            match devices.get(&index) {
+                Some(device) => diff(self.devices.get(&index), device, &mut self.events),
+                None => {
+                    if let Some(device) = self.devices.get(&index) {
+                        self.events.push_back(DeviceEvents::Removed(device.clone()));
+                    }
+                }
+            }
+        }
+
+        self.devices = devices;
+        Ok(())
+    }
+
+    fn wait(&self, deadline: Option<Instant>) -> io::Result<()> {
+        let timeout = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Err(io::Error::new(io::ErrorKind::TimedOut, "Timed out waiting for device events"));
+                }
+
+                remaining.as_nanos().div_ceil(1_000_000).min(i32::MAX as u128) as i32
+            }
+            None => -1
+        };
+
+        let mut fds = [PollFd {
+            fd: self.netlink.get_fd(),
+            events: POLLIN | POLLERR,
+            revents: 0
+        }];
+
+        let res = unsafe { poll(&mut fds, timeout) };
+        if res < 0 && -res as i32 != EINTR {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        if res == 0 {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "Timed out waiting for device events"));
+        }
+
+        Ok(())
+    }
+}
+
+impl AsRawFd for DeviceMonitor {
+
+    fn as_raw_fd(&self) -> RawFd {
+        self.netlink.get_fd()
+    }
+}
+
+impl Iterator for DeviceMonitor {
+
+    type Item = io::Result<DeviceEvents>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.recv())
+    }
+}
+
+pub(crate) fn diff(previous: Option<&Device>, device: &Device, events: &mut VecDeque<DeviceEvents>) {
+    let Some(previous) = previous else {
+        events.push_back(DeviceEvents::Added(device.clone()));
+        return;
+    };
+
+    if previous.get_name() != device.get_name() {
+        events.push_back(DeviceEvents::Renamed(device.clone(), previous.get_name()));
+    }
+
+    if previous.is_up() != device.is_up() {
+        events.push_back(if device.is_up() {
+            DeviceEvents::Up(device.clone())
+        } else {
+            DeviceEvents::Down(device.clone())
+        });
+
+    } else if previous.get_mtu() != device.get_mtu() || previous.get_mac() != device.get_mac()
+            || previous.get_flags() != device.get_flags() || previous.get_oper_state() != device.get_oper_state() {
+        events.push_back(DeviceEvents::Changed(device.clone()));
+    }
+
+    let addresses = device.get_addresses();
+    let previous_addresses = previous.get_addresses();
+
+    for address in &previous_addresses {
+        if !addresses.contains(address) {
+            events.push_back(DeviceEvents::AddressRemoved(device.clone(), *address));
+        }
+    }
+
+    for address in &addresses {
+        if !previous_addresses.contains(address) {
+            events.push_back(DeviceEvents::AddressAdded(device.clone(), *address));
+        }
+    }
+}
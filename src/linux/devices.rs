@@ -1,19 +1,32 @@
-use std::{io, mem};
+use std::{fs, io, mem};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
 use crate::utils::interface_flags::InterfaceFlags;
-use crate::linux::sys::{parse_ip, IfreqAddr, IfreqHwAddr, IfreqIndex, SockAddr, AF_INET, AF_INET6, IFNAMSIZ, SIOCGIFHWADDR, SIOCGIFINDEX, SOCK_DGRAM};
-use crate::linux::sys::{close, ioctl, socket, IfConf, IfreqFlags, SIOCGIFCONF, SIOCGIFFLAGS};
+use crate::linux::netlink::{parse_attributes, read_header, Netlink};
+use crate::linux::net_namespaces::NetNamespaces;
+use crate::linux::sys::{ioctl, socket, IfAddrMsg, IfInfoMsg, IfreqFlags, IfreqMtu, IFNAMSIZ, SIOCGIFFLAGS, SIOCSIFFLAGS, SIOCSIFMTU, SOCK_CLOEXEC, SOCK_DGRAM, RtMsg, RtNextHop, AF_INET, AF_INET6, RTA_MULTIPATH, RTA_OIF, RTA_PRIORITY, RTA_TABLE, RTM_GETROUTE, RTM_NEWROUTE, RTN_UNICAST, RT_TABLE_MAIN, IFA_ADDRESS, IFA_BROADCAST, IFA_LOCAL, IFLA_ADDRESS, IFLA_IFNAME, IFLA_INFO_KIND, IFLA_ALLMULTI, IFLA_LINKINFO, IFLA_MTU, IFLA_OPERSTATE, IFLA_PROMISCUITY, IFLA_STATS64, RtnlLinkStats64, RTM_GETADDR, RTM_GETLINK, RTM_NEWADDR, RTM_NEWLINK};
 use crate::utils::data_link_types::DataLinkTypes;
 use crate::packet::layers::ethernet_frame::inter::ethernet_address::EthernetAddress;
+use crate::utils::device_address::DeviceAddress;
+use crate::utils::device_details::DeviceDetails;
+use crate::utils::device_stats::{DeviceCounters, DeviceStats};
+use crate::utils::duplex_modes::DuplexModes;
+use crate::utils::oper_states::OperStates;
 
 #[derive(Clone, Debug)]
 pub struct Device {
     name: String,
     address: Option<IpAddr>,
+    addresses: Vec<DeviceAddress>,
     index: i32,
     data_link_type: DataLinkTypes,
+    hardware_type: u16,
     mac: Option<EthernetAddress>,
-    flags: Vec<InterfaceFlags>
+    flags: Vec<InterfaceFlags>,
+    mtu: u32,
+    oper_state: OperStates,
+    kind: Option<String>,
+    netns: Option<NetNamespaces>
 }
 
 impl Device {
@@ -22,98 +35,378 @@ impl Device {
         Self {
             name,
             address,
+            addresses: Vec::new(),
             index,
             data_link_type,
+            hardware_type: 0,
             mac,
-            flags
+            flags,
+            mtu: 0,
+            oper_state: OperStates::Unknown,
+            kind: None,
+            netns: None
         }
     }
 
     pub fn list() -> io::Result<Vec<Self>> {
-        let fd = unsafe { socket(AF_INET, SOCK_DGRAM, 0) };
-        if fd < 0 {
-            return Err(io::Error::last_os_error());
-        }
+        Self::dump(true)
+    }
+
+    fn dump(sysfs_fallback: bool) -> io::Result<Vec<Self>> {
+        let mut netlink = Netlink::new(0)?;
 
-        let mut ifreqs: [IfreqAddr; 32] = unsafe { mem::zeroed() };
-        let mut ifc = IfConf {
-            ifc_len: (mem::size_of::<IfreqAddr>() * ifreqs.len()) as i32,
-            ifc_buf: ifreqs.as_mut_ptr()
+        let request = IfInfoMsg {
+            ifi_family: 0,
+            ifi_pad: 0,
+            ifi_type: 0,
+            ifi_index: 0,
+            ifi_flags: 0,
+            ifi_change: 0
         };
 
+        let mut devices: Vec<Self> = netlink.dump(RTM_GETLINK, &request)?.iter()
+            .filter(|message| message._type == RTM_NEWLINK)
+            .filter_map(|message| Self::from_link(&message.payload, sysfs_fallback))
+            .collect();
 
-        let res = unsafe { ioctl(fd, SIOCGIFCONF as i64, &mut ifc as *mut _ as i64) };
+        let request = IfAddrMsg {
+            ifa_family: 0,
+            ifa_prefixlen: 0,
+            ifa_flags: 0,
+            ifa_scope: 0,
+            ifa_index: 0
+        };
 
-        if res < 0 {
-            unsafe { close(fd) };
-            return Err(io::Error::last_os_error());
+        for message in netlink.dump(RTM_GETADDR, &request)? {
+            if message._type != RTM_NEWADDR {
+                continue;
+            }
+
+            if let Some((index, address)) = parse_address(&message.payload) {
+                if let Some(device) = devices.iter_mut().find(|device| device.index == index) {
+                    device.add_address(address);
+                }
+            }
         }
 
-        let mut devices = Vec::new();
-        let count = ifc.ifc_len as usize / mem::size_of::<IfreqAddr>();
+        Ok(devices)
+    }
 
-        for i in 0..count {
-            let ifr = &ifreqs[i];
+    pub fn list_in_netns(netns: impl Into<NetNamespaces>) -> io::Result<Vec<Self>> {
+        let netns = netns.into();
 
-            let name = String::from_utf8_lossy(&ifr.ifr_name)
-                .trim_end_matches('\0')
-                .to_string();
+        //SYSFS SHOWS THE NAMESPACE IT WAS MOUNTED IN, NOT THE ONE WE ENTERED
+        let mut devices = netns.run(|| Self::dump(false))?;
+        for device in &mut devices {
+            device.netns = Some(netns.clone());
+        }
 
-            let address = parse_ip(&ifr.ifr_addr);
+        Ok(devices)
+    }
 
-            let mut ifr_index = IfreqIndex {
-                ifr_name: ifr.ifr_name,
-                ifr_ifindex: 0
-            };
+    pub fn from_name(name: &str) -> io::Result<Self> {
+        Self::list()?.into_iter()
+            .find(|device| device.name == name)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("Device {} not found", name)))
+    }
+
+    pub fn from_index(index: i32) -> io::Result<Self> {
+        Self::list()?.into_iter()
+            .find(|device| device.index == index)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("Device with index {} not found", index)))
+    }
 
-            //CHECK INTERFACE INDEX
-            if unsafe { ioctl(fd, SIOCGIFINDEX as i64, &mut ifr_index as *const _ as i64) } < 0 {
+    pub fn default_route() -> io::Result<Self> {
+        let mut netlink = Netlink::new(0)?;
+
+        let request = RtMsg {
+            rtm_family: 0,
+            rtm_dst_len: 0,
+            rtm_src_len: 0,
+            rtm_tos: 0,
+            rtm_table: 0,
+            rtm_protocol: 0,
+            rtm_scope: 0,
+            rtm_type: 0,
+            rtm_flags: 0
+        };
+
+        //IPV4 BEFORE IPV6, THEN LOWEST METRIC FIRST
+        let mut routes = Vec::new();
+        for message in netlink.dump(RTM_GETROUTE, &request)? {
+            if message._type != RTM_NEWROUTE {
                 continue;
             }
 
-            let index = ifr_index.ifr_ifindex;
+            if let Some((family, priority, indexes)) = parse_default_route(&message.payload) {
+                routes.extend(indexes.into_iter().map(|index| (family as i64 == AF_INET6, priority, index)));
+            }
+        }
+
+        routes.sort();
+
+        let devices = Self::list()?;
+        routes.iter()
+            .filter_map(|(_, _, index)| devices.iter().find(|device| device.index == *index))
+            .find(|device| device.is_up() && !device.flags.contains(&InterfaceFlags::Loopback))
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No device carries a default route"))
+    }
+
+    pub(crate) fn from_link(payload: &[u8], sysfs_fallback: bool) -> Option<Self> {
+        let (info, attributes) = read_header::<IfInfoMsg>(payload)?;
 
-            let mut ifreq_hwaddr = IfreqHwAddr {
-                ifr_name: ifr.ifr_name,
-                ifr_hwaddr: SockAddr {
-                    sa_family: 0,
-                    sa_data: [0; 14]
+        let mut name = None;
+        let mut mac = None;
+        let mut mtu = 0;
+        let mut oper_state = OperStates::Unknown;
+        let mut kind = None;
+        let mut promiscuity = None;
+        let mut all_multi = None;
+
+        for (_type, data) in parse_attributes(attributes) {
+            match _type {
+                IFLA_IFNAME => name = Some(String::from_utf8_lossy(data).trim_end_matches('\0').to_string()),
+                IFLA_ADDRESS if data.len() == 6 => mac = Some(EthernetAddress::new(data[0], data[1], data[2], data[3], data[4], data[5])),
+                IFLA_MTU if data.len() >= 4 => mtu = u32::from_ne_bytes([data[0], data[1], data[2], data[3]]),
+                IFLA_OPERSTATE if !data.is_empty() => oper_state = OperStates::from_code(data[0]).unwrap_or(OperStates::Unknown),
+                IFLA_PROMISCUITY if data.len() >= 4 => promiscuity = Some(u32::from_ne_bytes([data[0], data[1], data[2], data[3]])),
+                IFLA_ALLMULTI if data.len() >= 4 => all_multi = Some(u32::from_ne_bytes([data[0], data[1], data[2], data[3]])),
+                IFLA_LINKINFO => {
+                    kind = parse_attributes(data).into_iter()
+                        .find(|(_type, _)| *_type == IFLA_INFO_KIND)
+                        .map(|(_, kind)| String::from_utf8_lossy(kind).trim_end_matches('\0').to_string());
                 }
+                _ => {}
+            }
+        }
+
+        let name = name?;
+
+        //IFI_FLAGS ONLY CARRIES USER SET PROMISC / ALLMULTI, THE COUNTERS COVER THE KERNEL ONES
+        let code = match (promiscuity, all_multi) {
+            (Some(promiscuity), Some(all_multi)) => {
+                (if promiscuity > 0 { InterfaceFlags::Promiscuous.get_code() } else { 0 }) |
+                    (if all_multi > 0 { InterfaceFlags::AllMulti.get_code() } else { 0 })
+            }
+            //OLDER KERNELS LACK IFLA_ALLMULTI, FALL BACK TO SYSFS
+            _ if sysfs_fallback => fs::read_to_string(format!("/sys/class/net/{}/flags", name)).ok()
+                .and_then(|flags| u32::from_str_radix(flags.trim().trim_start_matches("0x"), 16).ok())
+                .unwrap_or(0),
+            _ => 0
+        };
+
+        Some(Self {
+            name,
+            address: None,
+            addresses: Vec::new(),
+            index: info.ifi_index,
+            data_link_type: DataLinkTypes::from_sll2_code(info.ifi_type).unwrap_or(DataLinkTypes::Null),
+            hardware_type: info.ifi_type,
+            mac,
+            flags: InterfaceFlags::from_code(info.ifi_flags | code),
+            mtu,
+            oper_state,
+            kind,
+            netns: None
+        })
+    }
+
+    pub fn stats(&self) -> io::Result<DeviceStats> {
+        let index = self.index;
+
+        //NETLINK ANSWERS FROM THE NAMESPACE THE SOCKET WAS OPENED IN, SYSFS WOULD NOT
+        let stats = self.in_netns(move || {
+            let mut netlink = Netlink::new(0)?;
+
+            let request = IfInfoMsg {
+                ifi_family: 0,
+                ifi_pad: 0,
+                ifi_type: 0,
+                ifi_index: 0,
+                ifi_flags: 0,
+                ifi_change: 0
+            };
+
+            Ok(netlink.dump(RTM_GETLINK, &request)?.iter()
+                .filter(|message| message._type == RTM_NEWLINK)
+                .find_map(|message| parse_link_stats(&message.payload, index)))
+        })?;
+
+        stats.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("No statistics for device {}", self.name)))
+    }
+
+    pub fn details(&self) -> io::Result<DeviceDetails> {
+        if self.netns.is_some() {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "Device details are read from sysfs, which does not follow the network namespace"));
+        }
+
+        let path = format!("/sys/class/net/{}", self.name);
+
+        //RESOLVES INTO /sys/devices/virtual/net FOR ANYTHING WITHOUT A BACKING BUS DEVICE
+        let _virtual = fs::canonicalize(&path)?.starts_with("/sys/devices/virtual");
+
+        //SPEED, DUPLEX AND CARRIER RETURN EINVAL WHILE THE LINK IS DOWN
+        let speed = fs::read_to_string(format!("{}/speed", path)).ok()
+            .and_then(|speed| speed.trim().parse::<i64>().ok())
+            .filter(|speed| *speed >= 0)
+            .map(|speed| speed as u32);
+
+        let duplex = match fs::read_to_string(format!("{}/duplex", path)).unwrap_or_default().trim() {
+            "half" => DuplexModes::Half,
+            "full" => DuplexModes::Full,
+            _ => DuplexModes::Unknown
+        };
+
+        let carrier = fs::read_to_string(format!("{}/carrier", path))
+            .map(|carrier| carrier.trim() == "1")
+            .unwrap_or(false);
+
+        let driver = fs::read_link(format!("{}/device/driver", path)).ok()
+            .and_then(|driver| driver.file_name().map(|name| name.to_string_lossy().to_string()));
+
+        Ok(DeviceDetails::new(speed, duplex, carrier, driver, self.kind.clone(), _virtual))
+    }
+
+    pub fn set_flags(&mut self, flags: &[InterfaceFlags]) -> io::Result<Vec<InterfaceFlags>> {
+        let code = flags.iter().fold(0, |code, flag| code | flag.get_code());
+        if code > u16::MAX as u32 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Flags above 0xFFFF are read only"));
+        }
+
+        self.apply_flags(|_| code)
+    }
+
+    pub fn set_up(&mut self, up: bool) -> io::Result<Vec<InterfaceFlags>> {
+        self.toggle_flag(InterfaceFlags::Up, up)
+    }
+
+    pub fn set_promiscuous(&mut self, promiscuous: bool) -> io::Result<Vec<InterfaceFlags>> {
+        self.toggle_flag(InterfaceFlags::Promiscuous, promiscuous)
+    }
+
+    pub fn set_all_multicast(&mut self, all_multicast: bool) -> io::Result<Vec<InterfaceFlags>> {
+        self.toggle_flag(InterfaceFlags::AllMulti, all_multicast)
+    }
+
+    fn toggle_flag(&mut self, flag: InterfaceFlags, enabled: bool) -> io::Result<Vec<InterfaceFlags>> {
+        self.apply_flags(|code| if enabled { code | flag.get_code() } else { code & !flag.get_code() })
+    }
+
+    fn apply_flags<F>(&mut self, update: F) -> io::Result<Vec<InterfaceFlags>>
+    where
+        F: FnOnce(u32) -> u32 + Send
+    {
+        let name = self.ifreq_name()?;
+
+        //READ, MODIFY, WRITE, THEN READ AGAIN SO WE REPORT WHAT THE KERNEL ACTUALLY KEPT
+        let code = self.in_netns(|| {
+            let fd = control_socket()?;
+
+            let mut ifreq = IfreqFlags {
+                ifr_name: name,
+                ifr_flags: 0,
+                ifr_pad: [0; 22]
             };
 
-            if unsafe { ioctl(fd, SIOCGIFHWADDR as i64, &mut ifreq_hwaddr as *mut _ as i64) } < 0 {
-                return Err(io::Error::last_os_error());
+            let res = unsafe { ioctl(fd.as_raw_fd(), SIOCGIFFLAGS as i64, &mut ifreq as *mut _ as i64) };
+            if res < 0 {
+                return Err(io::Error::from_raw_os_error(-res as i32));
             }
 
-            let data_link_type = Some(unsafe { DataLinkTypes::from_sll2_code(ifreq_hwaddr.ifr_hwaddr.sa_family)
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))? }).unwrap();
+            ifreq.ifr_flags = update(ifreq.ifr_flags as u16 as u32) as u16 as i16;
 
-            let mac_bytes = unsafe { ifreq_hwaddr.ifr_hwaddr.sa_data };
-            let mac = Some(EthernetAddress::new(mac_bytes[0], mac_bytes[1], mac_bytes[2], mac_bytes[3], mac_bytes[4], mac_bytes[5]));
+            let res = unsafe { ioctl(fd.as_raw_fd(), SIOCSIFFLAGS as i64, &mut ifreq as *mut _ as i64) };
+            if res < 0 {
+                return Err(io::Error::from_raw_os_error(-res as i32));
+            }
 
-            let mut ifr_flags = IfreqFlags {
-                ifr_name: ifr.ifr_name,
-                ifr_flags: 0
+            let res = unsafe { ioctl(fd.as_raw_fd(), SIOCGIFFLAGS as i64, &mut ifreq as *mut _ as i64) };
+            if res < 0 {
+                return Err(io::Error::from_raw_os_error(-res as i32));
+            }
+
+            Ok(ifreq.ifr_flags as u16 as u32)
+        })?;
+
+        self.flags = InterfaceFlags::from_code(code);
+        Ok(self.get_flags())
+    }
+
+    pub fn set_mtu(&mut self, mtu: u32) -> io::Result<()> {
+        if mtu > i32::MAX as u32 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "MTU out of range"));
+        }
+
+        let name = self.ifreq_name()?;
+
+        self.in_netns(|| {
+            let fd = control_socket()?;
+
+            let mut ifreq = IfreqMtu {
+                ifr_name: name,
+                ifr_mtu: mtu as i32,
+                ifr_pad: [0; 20]
             };
 
-            if unsafe { ioctl(fd, SIOCGIFFLAGS as i64, &mut ifr_flags as *mut _ as i64) } < 0 {
-                continue;
+            let res = unsafe { ioctl(fd.as_raw_fd(), SIOCSIFMTU as i64, &mut ifreq as *mut _ as i64) };
+            if res < 0 {
+                return Err(io::Error::from_raw_os_error(-res as i32));
             }
 
-            let flags = InterfaceFlags::from_code(ifr_flags.ifr_flags as u32);
+            Ok(())
+        })?;
+
+        self.mtu = mtu;
+        Ok(())
+    }
 
-            devices.push(Self {
-                name,
-                address,
-                index,
-                data_link_type,
-                mac,
-                flags
-            });
+    fn ifreq_name(&self) -> io::Result<[u8; IFNAMSIZ]> {
+        let if_name_bytes = self.name.as_bytes();
+        if if_name_bytes.len() >= IFNAMSIZ {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Interface name too long"));
         }
 
-        unsafe { close(fd) };
-        Ok(devices)
+        let mut name = [0; IFNAMSIZ];
+        name[..if_name_bytes.len()].copy_from_slice(if_name_bytes);
+        Ok(name)
+    }
+
+    fn in_netns<T, F>(&self, f: F) -> io::Result<T>
+    where
+        T: Send,
+        F: FnOnce() -> io::Result<T> + Send
+    {
+        match self.netns {
+            Some(ref netns) => netns.run(f),
+            None => f()
+        }
+    }
+
+    pub(crate) fn add_address(&mut self, address: DeviceAddress) {
+        if !self.addresses.contains(&address) {
+            self.addresses.push(address);
+        }
+
+        self.update_address();
+    }
+
+    pub(crate) fn remove_address(&mut self, address: &DeviceAddress) {
+        self.addresses.retain(|a| a != address);
+
+        self.update_address();
+    }
+
+    fn update_address(&mut self) {
+        //PRIMARY ADDRESS PREFERS IPV4 THE WAY SIOCGIFCONF USED TO REPORT IT
+        self.address = self.addresses.iter()
+            .find(|address| address.get_address().is_ipv4())
+            .or(self.addresses.first())
+            .map(DeviceAddress::get_address);
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.flags.contains(&InterfaceFlags::Up) && self.flags.contains(&InterfaceFlags::Running)
     }
 
     pub fn get_name(&self) -> String {
@@ -124,6 +417,10 @@ impl Device {
         self.address
     }
 
+    pub fn get_addresses(&self) -> Vec<DeviceAddress> {
+        self.addresses.clone()
+    }
+
     pub fn get_index(&self) -> i32 {
         self.index
     }
@@ -132,6 +429,10 @@ impl Device {
         self.data_link_type
     }
 
+    pub fn get_hardware_type(&self) -> u16 {
+        self.hardware_type
+    }
+
     pub fn get_mac(&self) -> Option<EthernetAddress> {
         self.mac
     }
@@ -139,4 +440,114 @@ impl Device {
     pub fn get_flags(&self) -> Vec<InterfaceFlags> {
         self.flags.clone()
     }
+
+    pub fn get_mtu(&self) -> u32 {
+        self.mtu
+    }
+
+    pub fn get_netns(&self) -> Option<&NetNamespaces> {
+        self.netns.as_ref()
+    }
+
+    pub fn get_oper_state(&self) -> OperStates {
+        self.oper_state
+    }
+}
+
+pub(crate) fn parse_address(payload: &[u8]) -> Option<(i32, DeviceAddress)> {
+    let (info, attributes) = read_header::<IfAddrMsg>(payload)?;
+
+    let mut local = None;
+    let mut address = None;
+    let mut broadcast = None;
+
+    for (_type, data) in parse_attributes(attributes) {
+        let ip = match (info.ifa_family as i64, data.len()) {
+            (AF_INET, 4) => IpAddr::V4(Ipv4Addr::new(data[0], data[1], data[2], data[3])),
+            (AF_INET6, 16) => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(data).ok()?)),
+            _ => continue
+        };
+
+        match _type {
+            IFA_LOCAL => local = Some(ip),
+            IFA_ADDRESS => address = Some(ip),
+            IFA_BROADCAST => broadcast = Some(ip),
+            _ => {}
+        }
+    }
+
+    //ON POINT TO POINT LINKS IFA_LOCAL IS OUR SIDE AND IFA_ADDRESS IS THE PEER
+    let (local, peer) = match (local, address) {
+        (Some(local), Some(address)) if local != address => (local, Some(address)),
+        (Some(local), _) => (local, None),
+        (None, Some(address)) => (address, None),
+        (None, None) => return None
+    };
+
+    Some((info.ifa_index as i32, DeviceAddress::new(local, info.ifa_prefixlen, broadcast, peer)))
+}
+
+pub(crate) fn parse_default_route(payload: &[u8]) -> Option<(u8, u32, Vec<i32>)> {
+    let (info, attributes) = read_header::<RtMsg>(payload)?;
+
+    if info.rtm_dst_len != 0 || info.rtm_type != RTN_UNICAST {
+        return None;
+    }
+
+    let mut table = info.rtm_table as u32;
+    let mut priority = 0;
+    let mut indexes = Vec::new();
+
+    for (_type, data) in parse_attributes(attributes) {
+        match _type {
+            RTA_TABLE if data.len() >= 4 => table = u32::from_ne_bytes([data[0], data[1], data[2], data[3]]),
+            RTA_PRIORITY if data.len() >= 4 => priority = u32::from_ne_bytes([data[0], data[1], data[2], data[3]]),
+            RTA_OIF if data.len() >= 4 => indexes.push(i32::from_ne_bytes([data[0], data[1], data[2], data[3]])),
+            RTA_MULTIPATH => {
+                let mut data = data;
+                while let Some((hop, _)) = read_header::<RtNextHop>(data) {
+                    if (hop.rtnh_len as usize) < mem::size_of::<RtNextHop>() || hop.rtnh_len as usize > data.len() {
+                        break;
+                    }
+
+                    indexes.push(hop.rtnh_ifindex);
+                    data = &data[((hop.rtnh_len as usize + 3) & !3).min(data.len())..];
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if table != RT_TABLE_MAIN {
+        return None;
+    }
+
+    Some((info.rtm_family, priority, indexes))
+}
+
+fn parse_link_stats(payload: &[u8], index: i32) -> Option<DeviceStats> {
+    let (info, attributes) = read_header::<IfInfoMsg>(payload)?;
+
+    if info.ifi_index != index {
+        return None;
+    }
+
+    //ONLY THE LEADING COUNTERS ARE READ, NEWER KERNELS APPEND MORE
+    let (stats, _) = parse_attributes(attributes).into_iter()
+        .find(|(_type, _)| *_type == IFLA_STATS64)
+        .and_then(|(_, data)| read_header::<RtnlLinkStats64>(data))?;
+
+    Some(DeviceStats::new(
+        DeviceCounters::new(stats.rx_bytes, stats.rx_packets, stats.rx_errors, stats.rx_dropped),
+        DeviceCounters::new(stats.tx_bytes, stats.tx_packets, stats.tx_errors, stats.tx_dropped)
+    ))
+}
+
+fn control_socket() -> io::Result<OwnedFd> {
+    let fd = unsafe { socket(AF_INET, SOCK_DGRAM | SOCK_CLOEXEC, 0) };
+    if fd < 0 {
+        return Err(io::Error::from_raw_os_error(-fd));
+    }
+
+    Ok(unsafe { OwnedFd::from_raw_fd(fd) })
 }
@@ -0,0 +1,34 @@
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum FanoutModes {
+    Hash,
+    LoadBalance,
+    Cpu,
+    Rollover,
+    Random,
+    QueueMapping
+}
+
+impl FanoutModes {
+
+    pub fn get_code(&self) -> u16 {
+        match self {
+            Self::Hash => 0,
+            Self::LoadBalance => 1,
+            Self::Cpu => 2,
+            Self::Rollover => 3,
+            Self::Random => 4,
+            Self::QueueMapping => 5
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Hash => "Hash",
+            Self::LoadBalance => "Load Balance",
+            Self::Cpu => "CPU",
+            Self::Rollover => "Rollover",
+            Self::Random => "Random",
+            Self::QueueMapping => "Queue Mapping"
+        }.to_string()
+    }
+}
@@ -0,0 +1,163 @@
+use std::{fs, io, mem};
+use std::collections::VecDeque;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use crate::devices::Device;
+use crate::linux::sys::{bind, sendto, setsockopt, socket, SockAddrIn, AF_PACKET, PACKET_QDISC_BYPASS, SOCK_RAW, SOL_PACKET};
+use crate::linux::tx_frame_statuses::TxFrameStatuses;
+use crate::linux::tx_ring::{TxRing, TxRingConfig};
+use crate::packet::layers::ethernet_frame::ethernet_frame::ETHERNET_FRAME_LEN;
+use crate::packet::layers::ethernet_frame::vlan::vlan_extension::VLAN_EXTENSION_LEN;
+use crate::packet::packet::Packet;
+use crate::pcap::pcap::Pcap;
+use crate::utils::data_link_types::DataLinkTypes;
+
+#[derive(Debug)]
+pub struct Injector {
+    //FIELDS DROP IN ORDER, THE RING MUST BE UNMAPPED BEFORE ITS SOCKET CLOSES
+    ring: TxRing,
+    fd: OwnedFd,
+    device: Device,
+    max_frame_length: usize,
+    pending: VecDeque<Option<usize>>,
+    statuses: Vec<TxFrameStatuses>
+}
+
+impl Injector {
+
+    pub fn new(device: &Device, config: TxRingConfig) -> io::Result<Self> {
+        //PROTOCOL 0 SO THE SOCKET NEVER QUEUES RECEIVED FRAMES
+        let fd = unsafe { socket(AF_PACKET, SOCK_RAW, 0) };
+        if fd < 0 {
+            return Err(io::Error::from_raw_os_error(-fd));
+        }
+
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let sockaddr = SockAddrIn {
+            sll_family: AF_PACKET as u16,
+            sll_protocol: 0,
+            sll_ifindex: device.get_index(),
+            sll_hatype: 0,
+            sll_pkttype: 0,
+            sll_halen: 0,
+            sll_addr: [0; 8]
+        };
+
+        let res = unsafe { bind(fd.as_raw_fd(), &sockaddr as *const _ as i64, mem::size_of::<SockAddrIn>() as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        let ring = TxRing::new(fd.as_raw_fd(), &config)?;
+
+        //KERNEL ACCEPTS MTU PLUS LINK HEADER AND ONE VLAN TAG
+        let mtu = fs::read_to_string(format!("/sys/class/net/{}/mtu", device.get_name())).ok()
+            .and_then(|mtu| mtu.trim().parse::<usize>().ok())
+            .unwrap_or(1500);
+
+        Ok(Self {
+            ring,
+            fd,
+            device: device.clone(),
+            max_frame_length: mtu + ETHERNET_FRAME_LEN + VLAN_EXTENSION_LEN,
+            pending: VecDeque::new(),
+            statuses: Vec::new()
+        })
+    }
+
+    pub fn set_qdisc_bypass(&self, bypass: bool) -> io::Result<()> {
+        let bypass = bypass as i32;
+        let res = unsafe { setsockopt(self.fd.as_raw_fd(), SOL_PACKET, PACKET_QDISC_BYPASS, &bypass as *const _ as i64, mem::size_of::<i32>() as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        Ok(())
+    }
+
+    pub fn queue(&mut self, packet: &Packet) -> io::Result<()> {
+        let data = packet.to_bytes();
+
+        if data.len() > self.max_frame_length || (self.device.get_data_link_type() == DataLinkTypes::En10mb && data.len() < ETHERNET_FRAME_LEN) {
+            self.pending.push_back(None);
+            return Ok(());
+        }
+
+        loop {
+            match self.ring.push(&data)? {
+                Some(index) => {
+                    self.pending.push_back(Some(index));
+                    return Ok(());
+                }
+                None => self.transmit()?
+            }
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<Vec<TxFrameStatuses>> {
+        self.transmit()?;
+        Ok(mem::take(&mut self.statuses))
+    }
+
+    pub fn send(&mut self, packet: &Packet) -> io::Result<TxFrameStatuses> {
+        self.queue(packet)?;
+
+        self.flush()?.pop()
+            .ok_or_else(|| io::Error::other("Frame status missing after transmit"))
+    }
+
+    pub fn replay(&mut self, pcap: &Pcap) -> io::Result<Vec<TxFrameStatuses>> {
+        if pcap.get_data_link_type() != self.device.get_data_link_type() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Pcap data link type does not match the device"));
+        }
+
+        for i in 0..pcap.total_packets() {
+            self.queue(pcap.get_packet(i))?;
+        }
+
+        self.flush()
+    }
+
+    pub fn get_device(&self) -> &Device {
+        &self.device
+    }
+
+    pub fn get_frame_count(&self) -> usize {
+        self.ring.get_frame_count()
+    }
+
+    fn transmit(&mut self) -> io::Result<()> {
+        while !self.pending.is_empty() {
+            //BLOCKING SEND RETURNS ONCE THE KERNEL HAS WALKED THE REQUESTED FRAMES OR HIT A MALFORMED ONE
+            let res = unsafe { sendto(self.fd.as_raw_fd(), &mut [], 0, None) };
+            let pending = self.pending.len();
+
+            while let Some(&index) = self.pending.front() {
+                let status = match index {
+                    Some(index) => self.ring.frame_status(index),
+                    None => TxFrameStatuses::WrongFormat
+                };
+
+                if status == TxFrameStatuses::SendRequest || status == TxFrameStatuses::Sending {
+                    break;
+                }
+
+                self.statuses.push(status);
+                self.pending.pop_front();
+
+                if let (Some(index), TxFrameStatuses::WrongFormat) = (index, status) {
+                    self.ring.discard(index, &mut self.pending);
+                }
+            }
+
+            if self.pending.len() == pending {
+                return Err(match res {
+                    res if res < 0 => io::Error::from_raw_os_error(-res as i32),
+                    _ => io::Error::new(io::ErrorKind::WouldBlock, "TX ring made no progress")
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
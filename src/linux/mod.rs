@@ -1,4 +1,19 @@
+#[cfg(feature = "tokio")]
+pub mod async_capture;
+pub mod break_loop;
 pub mod capture;
+pub mod capture_builder;
+pub mod device_events;
+pub mod device_monitor;
 pub mod devices;
-mod sys;
+pub mod fanout_modes;
+pub mod injector;
+pub mod net_namespaces;
+pub mod packet_buf;
+pub mod rx_ring;
+pub mod tx_frame_statuses;
+pub mod tun_modes;
+pub mod tx_ring;
+pub(crate) mod netlink;
+pub(crate) mod sys;
 mod sll2_types;
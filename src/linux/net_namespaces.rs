@@ -0,0 +1,79 @@
+use std::fs::File;
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::thread;
+use crate::linux::sys::{setns, CLONE_NEWNET};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetNamespaces {
+    Path(PathBuf),
+    Pid(u32)
+}
+
+impl NetNamespaces {
+
+    pub fn get_path(&self) -> PathBuf {
+        match self {
+            Self::Path(path) => path.clone(),
+            Self::Pid(pid) => PathBuf::from(format!("/proc/{}/ns/net", pid))
+        }
+    }
+
+    pub(crate) fn run<T, F>(&self, f: F) -> io::Result<T>
+    where
+        T: Send,
+        F: FnOnce() -> io::Result<T> + Send
+    {
+        let target = File::open(self.get_path())?;
+
+        //SETNS ONLY MOVES THE CALLING THREAD, DO IT ON ONE WE OWN SO THE CALLER NEVER SWITCHES
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                let original = File::open("/proc/thread-self/ns/net")?;
+
+                let res = unsafe { setns(target.as_raw_fd(), CLONE_NEWNET) };
+                if res < 0 {
+                    return Err(io::Error::from_raw_os_error(-res as i32));
+                }
+
+                let value = f();
+
+                let res = unsafe { setns(original.as_raw_fd(), CLONE_NEWNET) };
+                if res < 0 {
+                    return Err(io::Error::from_raw_os_error(-res as i32));
+                }
+
+                value
+            }).join().map_err(|_| io::Error::other("Namespace thread panicked"))?
+        })
+    }
+}
+
+impl From<u32> for NetNamespaces {
+
+    fn from(pid: u32) -> Self {
+        Self::Pid(pid)
+    }
+}
+
+impl From<PathBuf> for NetNamespaces {
+
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<&Path> for NetNamespaces {
+
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_path_buf())
+    }
+}
+
+impl From<&str> for NetNamespaces {
+
+    fn from(path: &str) -> Self {
+        Self::Path(PathBuf::from(path))
+    }
+}
@@ -0,0 +1,180 @@
+use std::{io, mem, ptr, slice};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use crate::linux::sys::{bind, recvmsg, sendto, socket, IoVec, MsgHdr, NlMsgHdr, RtAttr, SockAddrNl, AF_NETLINK, NETLINK_ROUTE, NLMSG_DONE, NLMSG_ERROR, NLM_F_DUMP, NLM_F_REQUEST, SOCK_CLOEXEC, SOCK_RAW};
+
+pub const NETLINK_BUFFER_LEN: usize = 1 << 15;
+
+#[derive(Debug, Clone)]
+pub struct NetlinkMessage {
+    pub _type: u16,
+    pub sequence: u32,
+    pub payload: Vec<u8>
+}
+
+#[derive(Debug)]
+pub struct Netlink {
+    fd: OwnedFd,
+    sequence: u32
+}
+
+impl Netlink {
+
+    pub fn new(groups: u32) -> io::Result<Self> {
+        let fd = unsafe { socket(AF_NETLINK, SOCK_RAW | SOCK_CLOEXEC, NETLINK_ROUTE) };
+        if fd < 0 {
+            return Err(io::Error::from_raw_os_error(-fd));
+        }
+
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let sockaddr = SockAddrNl {
+            nl_family: AF_NETLINK as u16,
+            nl_pad: 0,
+            nl_pid: 0,
+            nl_groups: groups
+        };
+
+        let res = unsafe { bind(fd.as_raw_fd(), &sockaddr as *const _ as i64, mem::size_of::<SockAddrNl>() as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        Ok(Self {
+            fd,
+            sequence: 0
+        })
+    }
+
+    pub fn dump<T: Copy>(&mut self, _type: u16, request: &T) -> io::Result<Vec<NetlinkMessage>> {
+        self.sequence = self.sequence.wrapping_add(1);
+
+        let header = NlMsgHdr {
+            nlmsg_len: (mem::size_of::<NlMsgHdr>() + mem::size_of::<T>()) as u32,
+            nlmsg_type: _type,
+            nlmsg_flags: NLM_F_REQUEST | NLM_F_DUMP,
+            nlmsg_seq: self.sequence,
+            nlmsg_pid: 0
+        };
+
+        let mut buffer = Vec::with_capacity(header.nlmsg_len as usize);
+        buffer.extend_from_slice(as_bytes(&header));
+        buffer.extend_from_slice(as_bytes(request));
+
+        //NO DESTINATION, AN UNCONNECTED NETLINK SOCKET SENDS TO THE KERNEL
+        let res = unsafe { sendto(self.fd.as_raw_fd(), &mut buffer, 0, None) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        let mut messages = Vec::new();
+
+        loop {
+            for message in self.recv(0)? {
+                if message.sequence != self.sequence {
+                    continue;
+                }
+
+                match message._type {
+                    NLMSG_DONE => return Ok(messages),
+                    NLMSG_ERROR => {
+                        let code = message.payload.get(..4)
+                            .map(|code| i32::from_ne_bytes([code[0], code[1], code[2], code[3]]))
+                            .unwrap_or(0);
+
+                        if code != 0 {
+                            return Err(io::Error::from_raw_os_error(-code));
+                        }
+                    }
+                    _ => messages.push(message)
+                }
+            }
+        }
+    }
+
+    pub fn recv(&self, flags: i64) -> io::Result<Vec<NetlinkMessage>> {
+        let mut buffer = vec![0u8; NETLINK_BUFFER_LEN];
+
+        let mut iov = IoVec {
+            iov_base: buffer.as_mut_ptr(),
+            iov_len: buffer.len()
+        };
+
+        let mut msg = MsgHdr {
+            msg_name: ptr::null_mut(),
+            msg_namelen: 0,
+            msg_iov: &mut iov,
+            msg_iovlen: 1,
+            msg_control: ptr::null_mut(),
+            msg_controllen: 0,
+            msg_flags: 0
+        };
+
+        let len = unsafe { recvmsg(self.fd.as_raw_fd(), &mut msg, flags) };
+        if len < 0 {
+            return Err(io::Error::from_raw_os_error(-len as i32));
+        }
+
+        let buffer = &buffer[..len as usize];
+        let header_length = mem::size_of::<NlMsgHdr>();
+        let mut messages = Vec::new();
+        let mut offset = 0;
+
+        while offset + header_length <= buffer.len() {
+            let header = unsafe { ptr::read_unaligned(buffer.as_ptr().add(offset) as *const NlMsgHdr) };
+            let length = header.nlmsg_len as usize;
+            if length < header_length || offset + length > buffer.len() {
+                break;
+            }
+
+            messages.push(NetlinkMessage {
+                _type: header.nlmsg_type,
+                sequence: header.nlmsg_seq,
+                payload: buffer[offset + header_length..offset + length].to_vec()
+            });
+
+            offset += align(length);
+        }
+
+        Ok(messages)
+    }
+
+    pub fn get_fd(&self) -> RawFd {
+        self.fd.as_raw_fd()
+    }
+}
+
+pub fn parse_attributes(buffer: &[u8]) -> Vec<(u16, &[u8])> {
+    let header_length = mem::size_of::<RtAttr>();
+    let mut attributes = Vec::new();
+    let mut offset = 0;
+
+    while offset + header_length <= buffer.len() {
+        let attribute = unsafe { ptr::read_unaligned(buffer.as_ptr().add(offset) as *const RtAttr) };
+        let length = attribute.rta_len as usize;
+        if length < header_length || offset + length > buffer.len() {
+            break;
+        }
+
+        attributes.push((attribute.rta_type, &buffer[offset + header_length..offset + length]));
+        offset += align(length);
+    }
+
+    attributes
+}
+
+pub fn read_header<T: Copy>(payload: &[u8]) -> Option<(T, &[u8])> {
+    if payload.len() < mem::size_of::<T>() {
+        return None;
+    }
+
+    let header = unsafe { ptr::read_unaligned(payload.as_ptr() as *const T) };
+    Some((header, &payload[align(mem::size_of::<T>()).min(payload.len())..]))
+}
+
+pub(crate) fn as_bytes<T>(value: &T) -> &[u8] {
+    unsafe { slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>()) }
+}
+
+fn align(length: usize) -> usize {
+    (length + 3) & !3
+}
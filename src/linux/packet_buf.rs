@@ -0,0 +1,81 @@
+use std::io;
+use crate::linux::capture::Capture;
+use crate::linux::sys::{SockAddrIn, TpacketAuxdata};
+use crate::packet::layers::sll2_frame::inter::packet_types::PacketTypes;
+use crate::packet::packet::Packet;
+use crate::utils::timestamp_sources::TimestampSources;
+
+#[derive(Debug, Clone)]
+pub struct PacketBuf {
+    pub(crate) buffer: Vec<u8>,
+    pub(crate) length: usize,
+    pub(crate) original_length: usize,
+    pub(crate) sockaddr: SockAddrIn,
+    pub(crate) frame_time: u128,
+    pub(crate) timestamp_source: TimestampSources,
+    pub(crate) auxdata: Option<TpacketAuxdata>,
+    pub(crate) cooked: bool,
+    pub(crate) control: [u8; 128]
+}
+
+impl PacketBuf {
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffer: vec![0; capacity],
+            length: 0,
+            original_length: 0,
+            sockaddr: SockAddrIn {
+                sll_family: 0,
+                sll_protocol: 0,
+                sll_ifindex: 0,
+                sll_hatype: 0,
+                sll_pkttype: 0,
+                sll_halen: 0,
+                sll_addr: [0; 8]
+            },
+            frame_time: 0,
+            timestamp_source: TimestampSources::User,
+            auxdata: None,
+            cooked: false,
+            control: [0; 128]
+        }
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        &self.buffer[..self.length]
+    }
+
+    pub fn get_capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub fn get_captured_length(&self) -> usize {
+        self.length
+    }
+
+    pub fn get_original_length(&self) -> usize {
+        self.original_length
+    }
+
+    pub fn get_sockaddr(&self) -> SockAddrIn {
+        self.sockaddr
+    }
+
+    pub fn get_frame_time(&self) -> u128 {
+        self.frame_time
+    }
+
+    pub fn get_timestamp_source(&self) -> TimestampSources {
+        self.timestamp_source
+    }
+
+    pub fn get_packet_type(&self) -> Option<PacketTypes> {
+        PacketTypes::from_code(self.sockaddr.sll_pkttype).ok()
+    }
+
+    pub fn to_packet(&self) -> io::Result<Packet> {
+        Capture::to_packet(self.cooked, self.sockaddr, self.frame_time, self.timestamp_source, self.original_length, self.auxdata, self.get_data())
+            .map(|(_, packet)| packet)
+    }
+}
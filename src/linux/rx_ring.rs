@@ -0,0 +1,211 @@
+use std::{io, mem, slice};
+use std::os::fd::RawFd;
+use std::ptr;
+use std::sync::atomic::{fence, Ordering};
+use std::time::Duration;
+use crate::linux::sys::{mmap, munmap, setsockopt, SockAddrIn, Tpacket3Hdr, TpacketAuxdata, TpacketBlockDesc, TpacketReq3, MAP_SHARED, PACKET_RX_RING, PACKET_VERSION, PROT_READ, PROT_WRITE, SOL_PACKET, TPACKET_ALIGNMENT, TPACKET_V3, TP_STATUS_KERNEL, TP_STATUS_TS_RAW_HARDWARE, TP_STATUS_USER};
+use crate::utils::timestamp_sources::TimestampSources;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RxRingConfig {
+    block_size: u32,
+    block_count: u32,
+    frame_size: u32,
+    retire_timeout: Duration
+}
+
+impl RxRingConfig {
+
+    pub fn new(block_size: u32, block_count: u32, frame_size: u32, retire_timeout: Duration) -> Self {
+        Self {
+            block_size,
+            block_count,
+            frame_size,
+            retire_timeout
+        }
+    }
+
+    pub fn set_block_size(&mut self, block_size: u32) {
+        self.block_size = block_size;
+    }
+
+    pub fn get_block_size(&self) -> u32 {
+        self.block_size
+    }
+
+    pub fn set_block_count(&mut self, block_count: u32) {
+        self.block_count = block_count;
+    }
+
+    pub fn get_block_count(&self) -> u32 {
+        self.block_count
+    }
+
+    pub fn set_frame_size(&mut self, frame_size: u32) {
+        self.frame_size = frame_size;
+    }
+
+    pub fn get_frame_size(&self) -> u32 {
+        self.frame_size
+    }
+
+    pub fn set_retire_timeout(&mut self, retire_timeout: Duration) {
+        self.retire_timeout = retire_timeout;
+    }
+
+    pub fn get_retire_timeout(&self) -> Duration {
+        self.retire_timeout
+    }
+}
+
+impl Default for RxRingConfig {
+
+    fn default() -> Self {
+        Self {
+            block_size: 1 << 20,
+            block_count: 64,
+            frame_size: 1 << 11,
+            retire_timeout: Duration::from_millis(60)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RxRing {
+    buffer: *mut u8,
+    length: usize,
+    block_size: usize,
+    block_count: usize,
+    block: usize,
+    remaining: u32,
+    offset: usize
+}
+
+unsafe impl Send for RxRing {}
+
+impl RxRing {
+
+    pub fn new(fd: RawFd, config: &RxRingConfig) -> io::Result<Self> {
+        if !(config.frame_size as usize).is_multiple_of(TPACKET_ALIGNMENT) || config.frame_size as usize <= mem::size_of::<Tpacket3Hdr>() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Frame size must be a multiple of 16 and larger than the frame header"));
+        }
+
+        if config.block_size == 0 || !config.block_size.is_multiple_of(config.frame_size) || config.block_count == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Block size must be a multiple of the frame size"));
+        }
+
+        let version = TPACKET_V3;
+        let res = unsafe { setsockopt(fd, SOL_PACKET, PACKET_VERSION, &version as *const _ as i64, mem::size_of::<i32>() as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        let req = TpacketReq3 {
+            tp_block_size: config.block_size,
+            tp_block_nr: config.block_count,
+            tp_frame_size: config.frame_size,
+            tp_frame_nr: (config.block_size / config.frame_size) * config.block_count,
+            tp_retire_blk_tov: config.retire_timeout.as_millis() as u32,
+            tp_sizeof_priv: 0,
+            tp_feature_req_word: 0
+        };
+
+        let res = unsafe { setsockopt(fd, SOL_PACKET, PACKET_RX_RING, &req as *const _ as i64, mem::size_of::<TpacketReq3>() as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        let length = config.block_size as usize * config.block_count as usize;
+        let buffer = unsafe { mmap(length, PROT_READ | PROT_WRITE, MAP_SHARED, fd) };
+
+        if buffer < 0 {
+            return Err(io::Error::from_raw_os_error(-buffer as i32));
+        }
+
+        Ok(Self {
+            buffer: buffer as *mut u8,
+            length,
+            block_size: config.block_size as usize,
+            block_count: config.block_count as usize,
+            block: 0,
+            remaining: 0,
+            offset: 0
+        })
+    }
+
+    pub fn next(&mut self) -> Option<(SockAddrIn, u128, TimestampSources, TpacketAuxdata, &[u8])> {
+        if self.remaining == 0 {
+            if self.offset != 0 {
+                self.release_block();
+            }
+
+            let desc = self.block_desc();
+            if unsafe { ptr::read_volatile(ptr::addr_of!((*desc).block_status)) } & TP_STATUS_USER == 0 {
+                return None;
+            }
+            fence(Ordering::Acquire);
+
+            self.remaining = unsafe { (*desc).num_pkts };
+            self.offset = unsafe { (*desc).offset_to_first_pkt } as usize;
+
+            if self.remaining == 0 {
+                self.release_block();
+                return None;
+            }
+        }
+
+        let frame = unsafe { self.block_ptr().add(self.offset) };
+        let hdr = unsafe { ptr::read(frame as *const Tpacket3Hdr) };
+
+        let sockaddr_offset = (mem::size_of::<Tpacket3Hdr>() + TPACKET_ALIGNMENT - 1) & !(TPACKET_ALIGNMENT - 1);
+        let sockaddr = unsafe { ptr::read_unaligned(frame.add(sockaddr_offset) as *const SockAddrIn) };
+
+        let frame_time = (hdr.tp_sec as u128 * 1_000_000_000) + hdr.tp_nsec as u128;
+        let timestamp_source = if hdr.tp_status & TP_STATUS_TS_RAW_HARDWARE != 0 {
+            TimestampSources::Hardware
+        } else {
+            TimestampSources::Software
+        };
+        let auxdata = TpacketAuxdata {
+            tp_status: hdr.tp_status,
+            tp_len: hdr.tp_len,
+            tp_snaplen: hdr.tp_snaplen,
+            tp_mac: hdr.tp_mac,
+            tp_net: hdr.tp_net,
+            tp_vlan_tci: hdr.tp_vlan_tci as u16,
+            tp_vlan_tpid: hdr.tp_vlan_tpid
+        };
+
+        let data = unsafe { slice::from_raw_parts(frame.add(hdr.tp_mac as usize), hdr.tp_snaplen as usize) };
+
+        self.remaining -= 1;
+        self.offset += hdr.tp_next_offset as usize;
+
+        Some((sockaddr, frame_time, timestamp_source, auxdata, data))
+    }
+
+    fn release_block(&mut self) {
+        let desc = self.block_desc();
+        fence(Ordering::Release);
+        unsafe { ptr::write_volatile(ptr::addr_of_mut!((*desc).block_status), TP_STATUS_KERNEL) };
+
+        self.block = (self.block + 1) % self.block_count;
+        self.remaining = 0;
+        self.offset = 0;
+    }
+
+    fn block_ptr(&self) -> *mut u8 {
+        unsafe { self.buffer.add(self.block * self.block_size) }
+    }
+
+    fn block_desc(&self) -> *mut TpacketBlockDesc {
+        self.block_ptr() as *mut TpacketBlockDesc
+    }
+}
+
+impl Drop for RxRing {
+
+    fn drop(&mut self) {
+        unsafe { munmap(self.buffer, self.length) };
+    }
+}
@@ -11,7 +11,12 @@ impl DataLinkTypes {
             (50, Self::Ipv6),
             //(108, Self::Bluetooth),
             //(276, Self::Raw), - Sll2
+            (768, Self::Raw),
+            (769, Self::Raw),
             (772, Self::En10mb),
+            (776, Self::Raw),
+            (778, Self::Raw),
+            (823, Self::Raw),
             (65534, Self::Raw)
         ];
 
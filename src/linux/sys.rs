@@ -1,32 +1,147 @@
 use std::arch::asm;
 use std::mem;
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::os::fd::RawFd;
+use crate::utils::sock_filter::SockFilter;
 
 pub const AF_PACKET: i64 = 17;
+pub const SOCK_DGRAM: i64 = 2;
 pub const SOCK_RAW: i64 = 3;
+pub const SOCK_NONBLOCK: i64 = 0x800;
+pub const O_NONBLOCK: i32 = 0x800;
 pub const ETH_P_ALL: u16 = 0x0003;
 pub const SOL_SOCKET: i64 = 1;
+pub const SOL_PACKET: i64 = 263;
+pub const SYS_READ: i64 = 0;
+pub const SYS_WRITE: i64 = 1;
+pub const SYS_POLL: i64 = 7;
+pub const SYS_MMAP: i64 = 9;
+pub const SYS_MUNMAP: i64 = 11;
 pub const SYS_SOCKET: i64 = 41;
 pub const SYS_CLOSE: i64 = 3;
 pub const SYS_IOCTL: i64 = 16;
+pub const SYS_DUP: i64 = 32;
 pub const SYS_BIND: i64 = 49;
 pub const SYS_SENDTO: i64 = 0x2C;
-pub const SYS_RECV_FROM: i64 = 45;
-//pub const SYS_GET_SOCK_OPT: i64 = 50;
+pub const SYS_RECVMSG: i64 = 47;
+pub const SYS_RECVMMSG: i64 = 299;
+pub const SYS_GET_SOCK_OPT: i64 = 55;
+pub const SYS_EVENTFD2: i64 = 290;
+pub const SYS_SETNS: i64 = 308;
 pub const SYS_SET_SOCK_OPT: i64 = 54;
 pub const SO_BINDTODEVICE: i64 = 25;
+pub const SO_ATTACH_FILTER: i64 = 26;
+pub const SO_DETACH_FILTER: i64 = 27;
+pub const SO_LOCK_FILTER: i64 = 44;
+pub const SO_TIMESTAMPNS: i64 = 35;
+pub const SO_TIMESTAMPING: i64 = 37;
+pub const SCM_TIMESTAMPNS: i64 = 35;
+pub const SCM_TIMESTAMPING: i64 = 37;
+pub const SOF_TIMESTAMPING_RX_HARDWARE: u32 = 1 << 2;
+pub const SOF_TIMESTAMPING_RX_SOFTWARE: u32 = 1 << 3;
+pub const SOF_TIMESTAMPING_SOFTWARE: u32 = 1 << 4;
+pub const SOF_TIMESTAMPING_RAW_HARDWARE: u32 = 1 << 6;
 pub const IFNAMSIZ: usize = 16;
-pub const SIOCGIFINDEX: u64 = 0x8933;
-pub const SIOCGIFCONF: u64 = 0x8912;
-pub const SIOCGIFADDR: u64 = 0x8915;
 pub const SIOCGIFFLAGS: u64 = 0x8913;
-pub const SIOCGIFHWADDR: u64 = 0x00008927;
+pub const SIOCSIFFLAGS: u64 = 0x8914;
+pub const SIOCGIFADDR: u64 = 0x8915;
+pub const SIOCSIFMTU: u64 = 0x8922;
+pub const SIOCSHWTSTAMP: u64 = 0x89b0;
+pub const HWTSTAMP_TX_OFF: i32 = 0;
+pub const HWTSTAMP_FILTER_ALL: i32 = 1;
 
 pub const AF_INET: i64 = 2;
 pub const AF_INET6: i64 = 10;
-pub const SOCK_DGRAM: i64 = 2;
+pub const AF_NETLINK: i64 = 16;
+pub const NETLINK_ROUTE: i64 = 0;
+pub const SOCK_CLOEXEC: i64 = 0x80000;
 pub const MSG_DONTWAIT: i64 = 0x40;
+pub const MSG_TRUNC: i64 = 0x20;
+pub const MSG_WAITFORONE: i64 = 0x10000;
+
+pub const NLMSG_ERROR: u16 = 2;
+pub const NLMSG_DONE: u16 = 3;
+pub const NLM_F_REQUEST: u16 = 0x1;
+pub const NLM_F_DUMP: u16 = 0x300;
+pub const RTM_NEWLINK: u16 = 16;
+pub const RTM_DELLINK: u16 = 17;
+pub const RTM_GETLINK: u16 = 18;
+pub const RTM_NEWADDR: u16 = 20;
+pub const RTM_DELADDR: u16 = 21;
+pub const RTM_GETADDR: u16 = 22;
+pub const RTM_NEWROUTE: u16 = 24;
+pub const RTM_GETROUTE: u16 = 26;
+pub const RTN_UNICAST: u8 = 1;
+pub const RT_TABLE_MAIN: u32 = 254;
+pub const RTMGRP_LINK: u32 = 0x1;
+pub const RTMGRP_IPV4_IFADDR: u32 = 0x10;
+pub const RTMGRP_IPV6_IFADDR: u32 = 0x100;
+pub const IFLA_ADDRESS: u16 = 1;
+pub const IFLA_IFNAME: u16 = 3;
+pub const IFLA_MTU: u16 = 4;
+pub const IFLA_OPERSTATE: u16 = 16;
+pub const IFLA_LINKINFO: u16 = 18;
+pub const IFLA_STATS64: u16 = 23;
+pub const IFLA_PROMISCUITY: u16 = 30;
+pub const IFLA_ALLMULTI: u16 = 61;
+pub const IFLA_INFO_KIND: u16 = 1;
+pub const IFA_ADDRESS: u16 = 1;
+pub const IFA_LOCAL: u16 = 2;
+pub const IFA_BROADCAST: u16 = 4;
+pub const RTA_OIF: u16 = 4;
+pub const RTA_PRIORITY: u16 = 6;
+pub const RTA_MULTIPATH: u16 = 9;
+pub const RTA_TABLE: u16 = 15;
+
+pub const PACKET_ADD_MEMBERSHIP: i64 = 1;
+pub const PACKET_DROP_MEMBERSHIP: i64 = 2;
+pub const PACKET_RX_RING: i64 = 5;
+pub const PACKET_STATISTICS: i64 = 6;
+pub const PACKET_AUXDATA: i64 = 8;
+pub const PACKET_TX_RING: i64 = 13;
+pub const PACKET_VERSION: i64 = 10;
+pub const PACKET_TIMESTAMP: i64 = 17;
+pub const PACKET_FANOUT: i64 = 18;
+pub const PACKET_FANOUT_FLAG_DEFRAG: u16 = 0x8000;
+pub const PACKET_QDISC_BYPASS: i64 = 20;
+pub const PACKET_IGNORE_OUTGOING: i64 = 23;
+pub const TPACKET_V2: i32 = 1;
+pub const TPACKET_V3: i32 = 2;
+pub const PACKET_MR_PROMISC: u16 = 1;
+pub const PACKET_MR_ALLMULTI: u16 = 2;
+pub const TPACKET_ALIGNMENT: usize = 16;
+pub const TP_STATUS_KERNEL: u32 = 0;
+pub const TP_STATUS_USER: u32 = 1;
+pub const TP_STATUS_AVAILABLE: u32 = 0;
+pub const TP_STATUS_SEND_REQUEST: u32 = 1;
+pub const TP_STATUS_WRONG_FORMAT: u32 = 4;
+pub const TP_STATUS_CSUMNOTREADY: u32 = 1 << 3;
+pub const TP_STATUS_VLAN_VALID: u32 = 1 << 4;
+pub const TP_STATUS_VLAN_TPID_VALID: u32 = 1 << 6;
+pub const TP_STATUS_CSUM_VALID: u32 = 1 << 7;
+pub const TP_STATUS_TS_RAW_HARDWARE: u32 = 1 << 31;
+
+pub const PROT_READ: i64 = 0x1;
+pub const PROT_WRITE: i64 = 0x2;
+pub const MAP_SHARED: i64 = 0x1;
+
+pub const EFD_NONBLOCK: i64 = 0x800;
+pub const EFD_CLOEXEC: i64 = 0x80000;
+pub const CLONE_NEWNET: i64 = 0x40000000;
+
+pub const TUNSETIFF: i64 = 0x400454CA;
+pub const TUNATTACHFILTER: i64 = 0x401054D5;
+pub const TUNDETACHFILTER: i64 = 0x401054D6;
+pub const IFF_NO_PI: i16 = 0x1000;
+pub const ARPHRD_ETHER: u16 = 1;
+pub const ARPHRD_NONE: u16 = 65534;
+
+pub const EINTR: i32 = 4;
+pub const EAGAIN: i32 = 11;
+pub const ENOBUFS: i32 = 105;
+
+pub const POLLIN: i16 = 0x1;
+pub const POLLOUT: i16 = 0x4;
+pub const POLLERR: i16 = 0x8;
 
 
 #[repr(C)]
@@ -37,44 +152,101 @@ pub struct IfreqName {
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
-pub struct IfreqAddr {
+pub struct IfreqFlags {
     pub ifr_name: [u8; IFNAMSIZ],
-    pub ifr_addr: [u8; 24]
+    pub ifr_flags: i16,
+    pub ifr_pad: [u8; 22]
 }
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
-pub struct IfreqHwAddr {
+pub struct IfreqMtu {
     pub ifr_name: [u8; IFNAMSIZ],
-    pub ifr_hwaddr: SockAddr
+    pub ifr_mtu: i32,
+    pub ifr_pad: [u8; 20]
 }
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
-pub struct IfConf {
-    pub ifc_len: i32,
-    pub ifc_buf: *mut IfreqAddr,
+pub struct SockAddrNl {
+    pub nl_family: u16,
+    pub nl_pad: u16,
+    pub nl_pid: u32,
+    pub nl_groups: u32
 }
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
-pub struct IfreqIndex {
-    pub ifr_name: [u8; IFNAMSIZ],
-    pub ifr_ifindex: i32
+pub struct NlMsgHdr {
+    pub nlmsg_len: u32,
+    pub nlmsg_type: u16,
+    pub nlmsg_flags: u16,
+    pub nlmsg_seq: u32,
+    pub nlmsg_pid: u32
 }
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
-pub struct IfreqFlags {
-    pub ifr_name: [u8; IFNAMSIZ],
-    pub ifr_flags: i16
+pub struct IfInfoMsg {
+    pub ifi_family: u8,
+    pub ifi_pad: u8,
+    pub ifi_type: u16,
+    pub ifi_index: i32,
+    pub ifi_flags: u32,
+    pub ifi_change: u32
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct RtnlLinkStats64 {
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct IfAddrMsg {
+    pub ifa_family: u8,
+    pub ifa_prefixlen: u8,
+    pub ifa_flags: u8,
+    pub ifa_scope: u8,
+    pub ifa_index: u32
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct RtMsg {
+    pub rtm_family: u8,
+    pub rtm_dst_len: u8,
+    pub rtm_src_len: u8,
+    pub rtm_tos: u8,
+    pub rtm_table: u8,
+    pub rtm_protocol: u8,
+    pub rtm_scope: u8,
+    pub rtm_type: u8,
+    pub rtm_flags: u32
 }
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
-pub struct SockAddr {
-    pub sa_family: u16,
-    pub sa_data: [u8; 14]
+pub struct RtNextHop {
+    pub rtnh_len: u16,
+    pub rtnh_flags: u8,
+    pub rtnh_hops: u8,
+    pub rtnh_ifindex: i32
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct RtAttr {
+    pub rta_len: u16,
+    pub rta_type: u16
 }
 
 #[repr(C)]
@@ -89,6 +261,182 @@ pub struct SockAddrIn {
     pub sll_addr: [u8; 8]
 }
 
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct PacketMreq {
+    pub mr_ifindex: i32,
+    pub mr_type: u16,
+    pub mr_alen: u16,
+    pub mr_address: [u8; 8]
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct SockFprog {
+    pub len: u16,
+    pub filter: *const SockFilter
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct TpacketReq {
+    pub tp_block_size: u32,
+    pub tp_block_nr: u32,
+    pub tp_frame_size: u32,
+    pub tp_frame_nr: u32
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct TpacketReq3 {
+    pub tp_block_size: u32,
+    pub tp_block_nr: u32,
+    pub tp_frame_size: u32,
+    pub tp_frame_nr: u32,
+    pub tp_retire_blk_tov: u32,
+    pub tp_sizeof_priv: u32,
+    pub tp_feature_req_word: u32
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct TpacketStatsV3 {
+    pub tp_packets: u32,
+    pub tp_drops: u32,
+    pub tp_freeze_q_cnt: u32
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct TpacketAuxdata {
+    pub tp_status: u32,
+    pub tp_len: u32,
+    pub tp_snaplen: u32,
+    pub tp_mac: u16,
+    pub tp_net: u16,
+    pub tp_vlan_tci: u16,
+    pub tp_vlan_tpid: u16
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct TpacketBdTs {
+    pub ts_sec: u32,
+    pub ts_nsec: u32
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct TpacketBlockDesc {
+    pub version: u32,
+    pub offset_to_priv: u32,
+    pub block_status: u32,
+    pub num_pkts: u32,
+    pub offset_to_first_pkt: u32,
+    pub blk_len: u32,
+    pub seq_num: u64,
+    pub ts_first_pkt: TpacketBdTs,
+    pub ts_last_pkt: TpacketBdTs
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct Tpacket2Hdr {
+    pub tp_status: u32,
+    pub tp_len: u32,
+    pub tp_snaplen: u32,
+    pub tp_mac: u16,
+    pub tp_net: u16,
+    pub tp_sec: u32,
+    pub tp_nsec: u32,
+    pub tp_vlan_tci: u16,
+    pub tp_vlan_tpid: u16,
+    pub tp_padding: [u8; 4]
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct Tpacket3Hdr {
+    pub tp_next_offset: u32,
+    pub tp_sec: u32,
+    pub tp_nsec: u32,
+    pub tp_snaplen: u32,
+    pub tp_len: u32,
+    pub tp_status: u32,
+    pub tp_mac: u16,
+    pub tp_net: u16,
+    pub tp_rxhash: u32,
+    pub tp_vlan_tci: u32,
+    pub tp_vlan_tpid: u16,
+    pub tp_padding: u16,
+    pub tp_padding_end: [u8; 8]
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct IoVec {
+    pub iov_base: *mut u8,
+    pub iov_len: usize
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct MsgHdr {
+    pub msg_name: *mut SockAddrIn,
+    pub msg_namelen: u32,
+    pub msg_iov: *mut IoVec,
+    pub msg_iovlen: usize,
+    pub msg_control: *mut u8,
+    pub msg_controllen: usize,
+    pub msg_flags: i32
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct MmsgHdr {
+    pub msg_hdr: MsgHdr,
+    pub msg_len: u32
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CmsgHdr {
+    pub cmsg_len: usize,
+    pub cmsg_level: i32,
+    pub cmsg_type: i32
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct TimeSpec {
+    pub tv_sec: i64,
+    pub tv_nsec: i64
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct HwtstampConfig {
+    pub flags: i32,
+    pub tx_type: i32,
+    pub rx_filter: i32
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct IfreqData {
+    pub ifr_name: [u8; IFNAMSIZ],
+    pub ifr_data: *mut HwtstampConfig,
+    pub ifr_pad: [u8; 16]
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct PollFd {
+    pub fd: i32,
+    pub events: i16,
+    pub revents: i16
+}
+
 pub unsafe fn socket(domain: i64, _type: i64, protocol: i64) -> RawFd {
     syscall(SYS_SOCKET, domain, _type, protocol, 0, 0) as RawFd
 }
@@ -101,23 +449,23 @@ pub unsafe fn ioctl(fd: RawFd, request: i64, arg: i64) -> i64 {
     syscall(SYS_IOCTL, fd as i64, request, arg, 0, 0)
 }
 
-pub unsafe fn sendto(fd: RawFd, buffer: &mut [u8]) -> i64 {
-    syscall(SYS_SENDTO, fd as i64, buffer.as_mut_ptr() as *mut _ as i64, buffer.len() as i64, 0, 0)
-}
-
-pub unsafe fn recvfrom(fd: RawFd, buffer: &mut [u8], flags: i64, sockaddr: &mut SockAddrIn) -> i64 {
-    let mut addr_len = mem::size_of::<SockAddrIn>() as u32;
+pub unsafe fn sendto(fd: RawFd, buffer: &mut [u8], flags: i64, sockaddr: Option<&SockAddrIn>) -> i64 {
     let ret: i64;
 
+    let (address, address_len) = match sockaddr {
+        Some(sockaddr) => (sockaddr as *const _ as i64, mem::size_of::<SockAddrIn>() as i64),
+        None => (0, 0)
+    };
+
     asm!(
         "syscall",
-        in("rax") SYS_RECV_FROM,
+        in("rax") SYS_SENDTO,
         in("rdi") fd as i64,
         in("rsi") buffer.as_mut_ptr() as *mut _ as i64,
         in("rdx") buffer.len(),
         in("r10") flags,
-        in("r8") sockaddr as *mut _ as *mut _ as i64,
-        in("r9") &mut addr_len as *mut u32,
+        in("r8") address,
+        in("r9") address_len,
         lateout("rax") ret,
         lateout("rcx") _,
         lateout("r11") _,
@@ -126,45 +474,76 @@ pub unsafe fn recvfrom(fd: RawFd, buffer: &mut [u8], flags: i64, sockaddr: &mut
     ret
 }
 
+pub unsafe fn recvmsg(fd: RawFd, msg: &mut MsgHdr, flags: i64) -> i64 {
+    syscall(SYS_RECVMSG, fd as i64, msg as *mut _ as i64, flags, 0, 0)
+}
+
+pub unsafe fn recvmmsg(fd: RawFd, msgs: &mut [MmsgHdr], flags: i64) -> i64 {
+    syscall(SYS_RECVMMSG, fd as i64, msgs.as_mut_ptr() as i64, msgs.len() as i64, flags, 0)
+}
+
+pub unsafe fn mmap(length: usize, prot: i64, flags: i64, fd: RawFd) -> i64 {
+    let ret: i64;
+
+    asm!(
+        "syscall",
+        in("rax") SYS_MMAP,
+        in("rdi") 0i64,
+        in("rsi") length as i64,
+        in("rdx") prot,
+        in("r10") flags,
+        in("r8") fd as i64,
+        in("r9") 0i64,
+        lateout("rax") ret,
+        lateout("rcx") _,
+        lateout("r11") _,
+    );
+
+    ret
+}
+
+pub unsafe fn munmap(address: *mut u8, length: usize) -> i64 {
+    syscall(SYS_MUNMAP, address as i64, length as i64, 0, 0, 0)
+}
+
+pub unsafe fn poll(fds: &mut [PollFd], timeout: i32) -> i64 {
+    syscall(SYS_POLL, fds.as_mut_ptr() as i64, fds.len() as i64, timeout as i64, 0, 0)
+}
+
+pub unsafe fn eventfd(initval: u32, flags: i64) -> i64 {
+    syscall(SYS_EVENTFD2, initval as i64, flags, 0, 0, 0)
+}
+
+pub unsafe fn read(fd: RawFd, buffer: &mut [u8]) -> i64 {
+    syscall(SYS_READ, fd as i64, buffer.as_mut_ptr() as i64, buffer.len() as i64, 0, 0)
+}
+
+pub unsafe fn write(fd: RawFd, buffer: &[u8]) -> i64 {
+    syscall(SYS_WRITE, fd as i64, buffer.as_ptr() as i64, buffer.len() as i64, 0, 0)
+}
+
+pub unsafe fn dup(fd: RawFd) -> i64 {
+    syscall(SYS_DUP, fd as i64, 0, 0, 0, 0)
+}
+
+pub unsafe fn setns(fd: RawFd, nstype: i64) -> i64 {
+    syscall(SYS_SETNS, fd as i64, nstype, 0, 0, 0)
+}
+
 pub unsafe fn close(fd: RawFd) {
     syscall(SYS_CLOSE, fd as i64, 0, 0, 0, 0);
 }
-/*
+
 pub unsafe fn getsockopt(fd: RawFd, level: i64, optname: i64, optval: i64, optlen: i64) -> i64 {
     syscall(SYS_GET_SOCK_OPT, fd as i64, level, optname, optval, optlen)
 }
-*/
+
 pub unsafe fn setsockopt(fd: RawFd, level: i64, optname: i64, optval: i64, optlen: i64) -> i64 {
     syscall(SYS_SET_SOCK_OPT, fd as i64, level, optname, optval, optlen)
 }
 
 pub unsafe fn syscall(number: i64, a1: i64, a2: i64, a3: i64, a4: i64, a5: i64) -> i64 {
     let ret: i64;
-    asm!("syscall", in("rax") number, in("rdi") a1, in("rsi") a2, in("rdx") a3, in("r10") a4, in("r8") a5, lateout("rax") ret);
+    asm!("syscall", in("rax") number, in("rdi") a1, in("rsi") a2, in("rdx") a3, in("r10") a4, in("r8") a5, lateout("rax") ret, lateout("rcx") _, lateout("r11") _);
     ret
 }
-
-pub fn parse_ip(buf: &[u8]) -> Option<IpAddr> {
-    if buf.len() < 5 {
-        return None;
-    }
-
-    match buf[0] as i64 {
-        AF_INET => {
-            if buf.len() < 8 {
-                return None;
-            }
-            Some(IpAddr::V4(Ipv4Addr::new(buf[4], buf[5], buf[6], buf[7])))
-        }
-        AF_INET6 => {
-            if buf.len() < 20 {
-                return None;
-            }
-            Some(IpAddr::V6(Ipv6Addr::from([
-                buf[4], buf[5], buf[6], buf[7], buf[8], buf[9], buf[10], buf[11],
-                buf[12], buf[13], buf[14], buf[15], buf[16], buf[17], buf[18], buf[19]
-            ])))
-        }
-        _ => None
-    }
-}
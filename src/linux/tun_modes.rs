@@ -0,0 +1,32 @@
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum TunModes {
+    Tun,
+    Tap
+}
+
+impl TunModes {
+
+    pub fn from_code(code: i16) -> Result<Self, String> {
+        for c in [Self::Tun, Self::Tap] {
+            if c.get_code() == code {
+                return Ok(c);
+            }
+        }
+
+        Err(format!("Couldn't find for code: {}", code))
+    }
+
+    pub fn get_code(&self) -> i16 {
+        match self {
+            Self::Tun => 0x1,
+            Self::Tap => 0x2
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Tun => "TUN",
+            Self::Tap => "TAP"
+        }.to_string()
+    }
+}
@@ -0,0 +1,38 @@
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum TxFrameStatuses {
+    Available,
+    SendRequest,
+    Sending,
+    WrongFormat
+}
+
+impl TxFrameStatuses {
+
+    pub fn from_code(code: u32) -> Result<Self, String> {
+        for c in [Self::Available, Self::SendRequest, Self::Sending, Self::WrongFormat] {
+            if c.get_code() == code {
+                return Ok(c);
+            }
+        }
+
+        Err(format!("Couldn't find for code: {}", code))
+    }
+
+    pub fn get_code(&self) -> u32 {
+        match self {
+            Self::Available => 0,
+            Self::SendRequest => 1,
+            Self::Sending => 2,
+            Self::WrongFormat => 4
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Available => "TP_STATUS_AVAILABLE",
+            Self::SendRequest => "TP_STATUS_SEND_REQUEST",
+            Self::Sending => "TP_STATUS_SENDING",
+            Self::WrongFormat => "TP_STATUS_WRONG_FORMAT"
+        }.to_string()
+    }
+}
@@ -0,0 +1,252 @@
+use std::{io, mem, ptr};
+use std::collections::VecDeque;
+use std::os::fd::RawFd;
+use std::sync::atomic::{fence, Ordering};
+use crate::linux::sys::{mmap, munmap, setsockopt, Tpacket2Hdr, TpacketReq, MAP_SHARED, PACKET_TX_RING, PACKET_VERSION, PROT_READ, PROT_WRITE, SOL_PACKET, TPACKET_ALIGNMENT, TPACKET_V2, TP_STATUS_AVAILABLE, TP_STATUS_SEND_REQUEST, TP_STATUS_WRONG_FORMAT};
+use crate::linux::tx_frame_statuses::TxFrameStatuses;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TxRingConfig {
+    block_size: u32,
+    block_count: u32,
+    frame_size: u32
+}
+
+impl TxRingConfig {
+
+    pub fn new(block_size: u32, block_count: u32, frame_size: u32) -> Self {
+        Self {
+            block_size,
+            block_count,
+            frame_size
+        }
+    }
+
+    pub fn set_block_size(&mut self, block_size: u32) {
+        self.block_size = block_size;
+    }
+
+    pub fn get_block_size(&self) -> u32 {
+        self.block_size
+    }
+
+    pub fn set_block_count(&mut self, block_count: u32) {
+        self.block_count = block_count;
+    }
+
+    pub fn get_block_count(&self) -> u32 {
+        self.block_count
+    }
+
+    pub fn set_frame_size(&mut self, frame_size: u32) {
+        self.frame_size = frame_size;
+    }
+
+    pub fn get_frame_size(&self) -> u32 {
+        self.frame_size
+    }
+}
+
+impl Default for TxRingConfig {
+
+    fn default() -> Self {
+        Self {
+            block_size: 1 << 16,
+            block_count: 64,
+            frame_size: 1 << 11
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct TxRing {
+    buffer: *mut u8,
+    length: usize,
+    //BACKING MEMORY WHEN THE RING IS NOT THE KERNEL MAPPING, U64 KEEPS THE FRAME HEADERS ALIGNED
+    heap: Option<Vec<u64>>,
+    block_size: usize,
+    frame_size: usize,
+    frames_per_block: usize,
+    frame_count: usize,
+    head: usize
+}
+
+unsafe impl Send for TxRing {}
+
+impl TxRing {
+
+    pub fn new(fd: RawFd, config: &TxRingConfig) -> io::Result<Self> {
+        Self::validate(config)?;
+
+        let version = TPACKET_V2;
+        let res = unsafe { setsockopt(fd, SOL_PACKET, PACKET_VERSION, &version as *const _ as i64, mem::size_of::<i32>() as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        let frames_per_block = config.block_size / config.frame_size;
+        let req = TpacketReq {
+            tp_block_size: config.block_size,
+            tp_block_nr: config.block_count,
+            tp_frame_size: config.frame_size,
+            tp_frame_nr: frames_per_block * config.block_count
+        };
+
+        let res = unsafe { setsockopt(fd, SOL_PACKET, PACKET_TX_RING, &req as *const _ as i64, mem::size_of::<TpacketReq>() as i64) };
+        if res < 0 {
+            return Err(io::Error::from_raw_os_error(-res as i32));
+        }
+
+        let length = config.block_size as usize * config.block_count as usize;
+        let buffer = unsafe { mmap(length, PROT_READ | PROT_WRITE, MAP_SHARED, fd) };
+
+        if buffer < 0 {
+            return Err(io::Error::from_raw_os_error(-buffer as i32));
+        }
+
+        Ok(Self::with_buffer(buffer as *mut u8, None, config))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn from_heap(config: &TxRingConfig) -> io::Result<Self> {
+        Self::validate(config)?;
+
+        let length = config.block_size as usize * config.block_count as usize;
+        let mut heap = vec![0u64; length.div_ceil(mem::size_of::<u64>())];
+        let buffer = heap.as_mut_ptr() as *mut u8;
+
+        Ok(Self::with_buffer(buffer, Some(heap), config))
+    }
+
+    fn validate(config: &TxRingConfig) -> io::Result<()> {
+        if !(config.frame_size as usize).is_multiple_of(TPACKET_ALIGNMENT) || config.frame_size as usize <= Self::data_offset() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Frame size must be a multiple of 16 and larger than the frame header"));
+        }
+
+        if config.block_size == 0 || !config.block_size.is_multiple_of(config.frame_size) || config.block_count == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Block size must be a multiple of the frame size"));
+        }
+
+        Ok(())
+    }
+
+    fn with_buffer(buffer: *mut u8, heap: Option<Vec<u64>>, config: &TxRingConfig) -> Self {
+        let frames_per_block = (config.block_size / config.frame_size) as usize;
+
+        Self {
+            buffer,
+            length: config.block_size as usize * config.block_count as usize,
+            heap,
+            block_size: config.block_size as usize,
+            frame_size: config.frame_size as usize,
+            frames_per_block,
+            frame_count: frames_per_block * config.block_count as usize,
+            head: 0
+        }
+    }
+
+    pub fn push(&mut self, data: &[u8]) -> io::Result<Option<usize>> {
+        if data.len() > self.frame_size - Self::data_offset() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Frame larger than the TX ring frame size"));
+        }
+
+        let index = self.head;
+        if self.status(index) != TP_STATUS_AVAILABLE {
+            return Ok(None);
+        }
+
+        let frame = self.frame_ptr(index);
+        unsafe {
+            ptr::copy_nonoverlapping(data.as_ptr(), frame.add(Self::data_offset()), data.len());
+            (*(frame as *mut Tpacket2Hdr)).tp_len = data.len() as u32;
+        }
+
+        fence(Ordering::Release);
+        self.set_status(index, TP_STATUS_SEND_REQUEST);
+
+        self.head = (self.head + 1) % self.frame_count;
+        Ok(Some(index))
+    }
+
+    pub fn frame_status(&self, index: usize) -> TxFrameStatuses {
+        let status = self.status(index);
+        fence(Ordering::Acquire);
+
+        if status & TP_STATUS_WRONG_FORMAT != 0 {
+            return TxFrameStatuses::WrongFormat;
+        }
+
+        TxFrameStatuses::from_code(status).unwrap_or(TxFrameStatuses::Sending)
+    }
+
+    //KERNEL STOPS ON A WRONG_FORMAT SLOT AND WILL NOT MOVE PAST IT, SO EVERY FRAME QUEUED BEHIND IT SHIFTS BACK ONE SLOT
+    pub fn discard(&mut self, index: usize, pending: &mut VecDeque<Option<usize>>) {
+        let mut slot = index;
+
+        loop {
+            let next = (slot + 1) % self.frame_count;
+            if next == self.head {
+                break;
+            }
+
+            let frame = self.frame_ptr(slot);
+            let source = self.frame_ptr(next);
+            unsafe {
+                let length = (*(source as *const Tpacket2Hdr)).tp_len;
+                ptr::copy_nonoverlapping(source.add(Self::data_offset()), frame.add(Self::data_offset()), length as usize);
+                (*(frame as *mut Tpacket2Hdr)).tp_len = length;
+            }
+
+            fence(Ordering::Release);
+            self.set_status(slot, TP_STATUS_SEND_REQUEST);
+            slot = next;
+        }
+
+        self.set_status(slot, TP_STATUS_AVAILABLE);
+        self.head = slot;
+
+        for index in pending.iter_mut().flatten() {
+            *index = (*index + self.frame_count - 1) % self.frame_count;
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn frame_data(&self, index: usize) -> &[u8] {
+        let frame = self.frame_ptr(index);
+        unsafe {
+            let length = (*(frame as *const Tpacket2Hdr)).tp_len as usize;
+            std::slice::from_raw_parts(frame.add(Self::data_offset()), length.min(self.frame_size - Self::data_offset()))
+        }
+    }
+
+    pub fn get_frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    fn data_offset() -> usize {
+        (mem::size_of::<Tpacket2Hdr>() + TPACKET_ALIGNMENT - 1) & !(TPACKET_ALIGNMENT - 1)
+    }
+
+    fn frame_ptr(&self, index: usize) -> *mut u8 {
+        let block = index / self.frames_per_block;
+        let frame = index % self.frames_per_block;
+        unsafe { self.buffer.add(block * self.block_size + frame * self.frame_size) }
+    }
+
+    pub(crate) fn status(&self, index: usize) -> u32 {
+        unsafe { ptr::read_volatile(ptr::addr_of!((*(self.frame_ptr(index) as *const Tpacket2Hdr)).tp_status)) }
+    }
+
+    pub(crate) fn set_status(&self, index: usize, status: u32) {
+        unsafe { ptr::write_volatile(ptr::addr_of_mut!((*(self.frame_ptr(index) as *mut Tpacket2Hdr)).tp_status), status) };
+    }
+}
+
+impl Drop for TxRing {
+
+    fn drop(&mut self) {
+        if self.heap.is_none() {
+            unsafe { munmap(self.buffer, self.length) };
+        }
+    }
+}
@@ -4,9 +4,12 @@ use std::fs::OpenOptions;
 use std::io::Read;
 use std::os::fd::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use crate::devices::Device;
+use crate::inter::capture_source::CaptureSource;
 use crate::macos::sys::{ioctl, recvfrom, select, TimeVal, Ifreq, BIOCGBLEN, BIOCIMMEDIATE, BIOCSETIF, IFNAMSIZ, close};
 use crate::utils::data_link_types::DataLinkTypes;
 use crate::packet::packet::Packet;
+use crate::utils::capture_stats::CaptureStats;
+use crate::utils::sock_filter::SockFilter;
 
 #[derive(Debug, Clone)]
 pub struct Capture {
@@ -113,7 +116,7 @@ impl Capture {
                 let datalen = u32::from_ne_bytes(buf[offset + 12..offset + 16].try_into().unwrap());
                 let hdrlen = u16::from_ne_bytes(buf[offset + 16..offset + 18].try_into().unwrap());
 
-                let packet = Packet::new(self.device.as_ref().unwrap().get_data_link_type(), 0, &buf[offset + hdrlen as usize..(offset + hdrlen as usize + caplen as usize)]);
+                let packet = Packet::new(self.device.as_ref().unwrap().get_data_link_type(), 0, &buf[offset + hdrlen as usize..(offset + hdrlen as usize + caplen as usize)])?;
 
                 match ret {
                     Some(_) => {
@@ -174,3 +177,36 @@ impl Capture {
         self.device.as_ref()
     }
 }
+
+impl CaptureSource for Capture {
+
+    fn open(&mut self) -> io::Result<()> {
+        Capture::open(self)
+    }
+
+    fn recv(&self) -> io::Result<Packet> {
+        Capture::recv(self).map(|(_, packet)| packet)
+    }
+
+    fn try_recv(&self) -> io::Result<Packet> {
+        Capture::try_recv(self).map(|(_, packet)| packet)
+    }
+
+    fn send(&self, _packet: Packet) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Sending is not supported on this platform yet"))
+    }
+
+    fn stats(&self) -> io::Result<CaptureStats> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Capture stats are not supported on this platform yet"))
+    }
+
+    fn set_filter(&self, _filter: &[SockFilter]) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Filters are not supported on this platform yet"))
+    }
+
+    fn get_data_link_type(&self) -> DataLinkTypes {
+        self.device.as_ref()
+            .map(Device::get_data_link_type)
+            .unwrap_or(DataLinkTypes::Null)
+    }
+}
@@ -119,10 +119,10 @@ impl Layer for ArpExtension {
 
         Some(Self {
             hardware_type: u16::from_be_bytes([buf[0], buf[1]]),
-            protocol_type: EthernetTypes::from_code(u16::from_be_bytes([buf[2], buf[3]])).unwrap(),
+            protocol_type: EthernetTypes::from_code(u16::from_be_bytes([buf[2], buf[3]])).ok()?,
             hardware_size: buf[4],
             protocol_size: buf[5],
-            opcode: ArpOperations::from_code(u16::from_be_bytes([buf[6], buf[7]])).unwrap(),
+            opcode: ArpOperations::from_code(u16::from_be_bytes([buf[6], buf[7]])).ok()?,
             sender_mac: EthernetAddress::new(buf[8], buf[9], buf[10], buf[11], buf[12], buf[13]),
             sender_address: Ipv4Addr::new(buf[14], buf[15], buf[16], buf[17]),
             target_mac: EthernetAddress::new(buf[18], buf[19], buf[20], buf[21], buf[22], buf[23]),
@@ -3,9 +3,11 @@ use crate::packet::layers::ethernet_frame::arp::arp_extension::ArpExtension;
 use crate::packet::layers::ethernet_frame::inter::ethernet_address::EthernetAddress;
 use crate::packet::layers::ethernet_frame::inter::ethernet_types::EthernetTypes;
 use crate::packet::layers::ethernet_frame::llc::llc_extension::LlcExtension;
+use crate::packet::layers::ethernet_frame::vlan::vlan_extension::VlanExtension;
 use crate::packet::layers::ip::ipv4_layer::Ipv4Layer;
 use crate::packet::layers::ip::ipv6_layer::Ipv6Layer;
 use crate::packet::layers::inter::layer::Layer;
+use crate::packet::layers::payload::payload_layer::PayloadLayer;
 
 pub const ETHERNET_FRAME_LEN: usize = 14;
 
@@ -75,14 +77,18 @@ impl Layer for EthernetFrame {
             return None;
         }
 
-        let _type = EthernetTypes::from_code(u16::from_be_bytes([buf[12], buf[13]])).expect("Unsupported Ethernet Type");
+        let _type = EthernetTypes::from_code(u16::from_be_bytes([buf[12], buf[13]])).ok()?;
+        let payload = &buf[ETHERNET_FRAME_LEN..];
+
+        //A HEADER CUT SHORT BY THE SNAPLEN OR WITHOUT A DISSECTOR IS KEPT AS RAW BYTES
         let data = match _type {
-            EthernetTypes::Ipv4 => Some(Ipv4Layer::from_bytes(&buf[ETHERNET_FRAME_LEN..])?.upcast()),
-            EthernetTypes::Arp => Some(ArpExtension::from_bytes(&buf[ETHERNET_FRAME_LEN..])?.upcast()),
-            EthernetTypes::Ipv6 => Some(Ipv6Layer::from_bytes(&buf[ETHERNET_FRAME_LEN..])?.upcast()),
-            EthernetTypes::Broadcast => None,
-            EthernetTypes::Length(_) => Some(LlcExtension::from_bytes(&buf[ETHERNET_FRAME_LEN..])?.upcast())
-        };
+            EthernetTypes::Ipv4 => Ipv4Layer::from_bytes(payload).map(Ipv4Layer::upcast),
+            EthernetTypes::Arp => ArpExtension::from_bytes(payload).map(ArpExtension::upcast),
+            EthernetTypes::Ipv6 => Ipv6Layer::from_bytes(payload).map(Ipv6Layer::upcast),
+            EthernetTypes::Vlan | EthernetTypes::QinQ => VlanExtension::from_bytes(payload).map(VlanExtension::upcast),
+            EthernetTypes::Rarp | EthernetTypes::Broadcast | EthernetTypes::Unknown(_) => None,
+            EthernetTypes::Length(_) => LlcExtension::from_bytes(payload).map(LlcExtension::upcast)
+        }.or_else(|| PayloadLayer::from_bytes(payload).map(PayloadLayer::upcast));
 
         Some(Self {
             destination_mac: EthernetAddress::new(buf[0], buf[1], buf[2], buf[3], buf[4], buf[5]),
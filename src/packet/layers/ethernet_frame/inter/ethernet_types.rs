@@ -1,12 +1,16 @@
-use crate::packet::layers::ethernet_frame::inter::ethernet_types::EthernetTypes::Length;
+use crate::packet::layers::ethernet_frame::inter::ethernet_types::EthernetTypes::{Length, Unknown};
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum EthernetTypes {
     Ipv4,
     Arp,
+    Rarp,
     Ipv6,
+    Vlan,
+    QinQ,
     Broadcast,
-    Length(u16)
+    Length(u16),
+    Unknown(u16)
 }
 
 impl EthernetTypes {
@@ -16,24 +20,26 @@ impl EthernetTypes {
             return Ok(Length(code));
         }
 
-        if code >= 1536 {
-            for c in [Self::Ipv4, Self::Arp, Self::Ipv6, Self::Broadcast] {
-                if c.get_code() == code {
-                    return Ok(c);
-                }
+        for c in [Self::Ipv4, Self::Arp, Self::Rarp, Self::Ipv6, Self::Vlan, Self::QinQ, Self::Broadcast] {
+            if c.get_code() == code {
+                return Ok(c);
             }
         }
 
-        Err(format!("Couldn't find for code: {}", code))
+        Ok(Unknown(code))
     }
 
     pub fn get_code(&self) -> u16 {
         match self {
             Self::Ipv4 => 2048,
             Self::Arp => 2054,
+            Self::Rarp => 32821,
             Self::Ipv6 => 34525,
+            Self::Vlan => 33024,
+            Self::QinQ => 34984,
             Self::Broadcast => 34969,
-            Length(n) => *n
+            Length(n) => *n,
+            Unknown(n) => *n
         }
     }
 
@@ -41,9 +47,13 @@ impl EthernetTypes {
         match self {
             Self::Ipv4 => "IPv4",
             Self::Arp => "ARP",
+            Self::Rarp => "RARP",
             Self::Ipv6 => "IPv6",
+            Self::Vlan => "802.1Q",
+            Self::QinQ => "802.1ad",
             Self::Broadcast => "Broadcast",
-            Length(_) => "Length"
+            Length(_) => "Length",
+            Unknown(_) => "Unknown"
         }.to_string()
     }
 }
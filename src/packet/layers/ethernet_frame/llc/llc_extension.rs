@@ -58,7 +58,7 @@ impl Layer for LlcExtension {
         Some(Self {
             dsap: buf[0],
             ssap: buf[1],
-            control: LlcTypes::from_code(buf[2]).unwrap()
+            control: LlcTypes::from_code(buf[2]).ok()?
         })
     }
 
@@ -1,4 +1,5 @@
 pub mod inter;
 pub mod arp;
 pub mod llc;
+pub mod vlan;
 pub mod ethernet_frame;
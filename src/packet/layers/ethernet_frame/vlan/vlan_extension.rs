@@ -0,0 +1,150 @@
+use std::any::Any;
+use crate::packet::layers::ethernet_frame::arp::arp_extension::ArpExtension;
+use crate::packet::layers::ethernet_frame::inter::ethernet_types::EthernetTypes;
+use crate::packet::layers::ethernet_frame::llc::llc_extension::LlcExtension;
+use crate::packet::layers::ip::ipv4_layer::Ipv4Layer;
+use crate::packet::layers::ip::ipv6_layer::Ipv6Layer;
+use crate::packet::layers::inter::layer::Layer;
+use crate::packet::layers::payload::payload_layer::PayloadLayer;
+
+pub const VLAN_EXTENSION_LEN: usize = 4;
+
+#[derive(Clone, Debug)]
+pub struct VlanExtension {
+    priority: u8,
+    drop_eligible: bool,
+    id: u16,
+    _type: EthernetTypes,
+    data: Option<Box<dyn Layer>>,
+    length: usize
+}
+
+impl VlanExtension {
+
+    pub fn new(priority: u8, drop_eligible: bool, id: u16, _type: EthernetTypes) -> Self {
+        Self {
+            priority,
+            drop_eligible,
+            id,
+            _type,
+            data: None,
+            length: VLAN_EXTENSION_LEN
+        }
+    }
+
+    pub fn set_priority(&mut self, priority: u8) {
+        self.priority = priority;
+    }
+
+    pub fn get_priority(&self) -> u8 {
+        self.priority
+    }
+
+    pub fn set_drop_eligible(&mut self, drop_eligible: bool) {
+        self.drop_eligible = drop_eligible;
+    }
+
+    pub fn get_drop_eligible(&self) -> bool {
+        self.drop_eligible
+    }
+
+    pub fn set_id(&mut self, id: u16) {
+        self.id = id;
+    }
+
+    pub fn get_id(&self) -> u16 {
+        self.id
+    }
+
+    pub fn set_type(&mut self, _type: EthernetTypes) {
+        self._type = _type;
+    }
+
+    pub fn get_type(&self) -> EthernetTypes {
+        self._type
+    }
+
+    pub fn get_data<T: 'static>(&self) -> Option<&T> {
+        self.data.as_ref()?.as_any().downcast_ref::<T>()
+    }
+
+    pub fn get_data_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.data.as_mut()?.as_any_mut().downcast_mut::<T>()
+    }
+
+    pub fn set_data(&mut self, data: Box<dyn Layer>) {
+        self.length = data.len() + VLAN_EXTENSION_LEN;
+        self.data = Some(data);
+    }
+}
+
+impl Layer for VlanExtension {
+
+    fn from_bytes(buf: &[u8]) -> Option<Self> {
+        if buf.len() < VLAN_EXTENSION_LEN {
+            return None;
+        }
+
+        let tci = u16::from_be_bytes([buf[0], buf[1]]);
+        let _type = EthernetTypes::from_code(u16::from_be_bytes([buf[2], buf[3]])).ok()?;
+        let payload = &buf[VLAN_EXTENSION_LEN..];
+
+        //A HEADER CUT SHORT BY THE SNAPLEN OR WITHOUT A DISSECTOR IS KEPT AS RAW BYTES
+        let data = match _type {
+            EthernetTypes::Ipv4 => Ipv4Layer::from_bytes(payload).map(Ipv4Layer::upcast),
+            EthernetTypes::Arp => ArpExtension::from_bytes(payload).map(ArpExtension::upcast),
+            EthernetTypes::Ipv6 => Ipv6Layer::from_bytes(payload).map(Ipv6Layer::upcast),
+            EthernetTypes::Vlan | EthernetTypes::QinQ => Self::from_bytes(payload).map(Self::upcast),
+            EthernetTypes::Rarp | EthernetTypes::Broadcast | EthernetTypes::Unknown(_) => None,
+            EthernetTypes::Length(_) => LlcExtension::from_bytes(payload).map(LlcExtension::upcast)
+        }.or_else(|| PayloadLayer::from_bytes(payload).map(PayloadLayer::upcast));
+
+        Some(Self {
+            priority: (tci >> 13) as u8,
+            drop_eligible: tci & 0x1000 != 0,
+            id: tci & 0x0fff,
+            _type,
+            data,
+            length: buf.len()
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let tci = ((self.priority as u16 & 0x07) << 13) | ((self.drop_eligible as u16) << 12) | (self.id & 0x0fff);
+
+        let mut buf = vec![0; VLAN_EXTENSION_LEN];
+        buf.splice(0..2, tci.to_be_bytes());
+        buf.splice(2..4, self._type.get_code().to_be_bytes());
+
+        if let Some(data) = &self.data {
+            buf.extend(data.to_bytes());
+        }
+
+        buf
+    }
+
+    fn len(&self) -> usize {
+        self.length
+    }
+
+    fn compute_length(&mut self) -> usize {
+        self.length = match &mut self.data {
+            Some(layer) => layer.compute_length() + VLAN_EXTENSION_LEN,
+            None => VLAN_EXTENSION_LEN
+        };
+
+        self.length
+    }
+
+    fn dyn_clone(&self) -> Box<dyn Layer> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
@@ -1,3 +1,5 @@
+use crate::packet::layers::ip::inter::ip_protocols::IpProtocols::Unknown;
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum IpProtocols {
     HopByHop,
@@ -9,7 +11,8 @@ pub enum IpProtocols {
     Gre,
     Icmpv6,
     Ospf,
-    Sps
+    Sps,
+    Unknown(u8)
 }
 
 impl IpProtocols {
@@ -21,7 +24,7 @@ impl IpProtocols {
             }
         }
 
-        Err(format!("Couldn't find for code: {}", code))
+        Ok(Unknown(code))
     }
 
     pub fn get_code(&self) -> u8 {
@@ -35,7 +38,8 @@ impl IpProtocols {
             Self::Gre => 47,
             Self::Icmpv6 => 58,
             Self::Ospf => 89,
-            Self::Sps => 128
+            Self::Sps => 128,
+            Unknown(n) => *n
         }
     }
 
@@ -50,7 +54,8 @@ impl IpProtocols {
             Self::Gre => "GRE",
             Self::Icmpv6 => "ICMPv6",
             Self::Ospf => "OSPF",
-            Self::Sps => "SPS"
+            Self::Sps => "SPS",
+            Unknown(_) => "Unknown"
         }.to_string()
     }
 }
@@ -1,7 +1,10 @@
+use crate::packet::layers::ip::inter::ip_versions::IpVersions::Unknown;
+
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum IpVersions {
     Ipv4,
-    Ipv6
+    Ipv6,
+    Unknown(u8)
 }
 
 impl IpVersions {
@@ -13,20 +16,22 @@ impl IpVersions {
             }
         }
 
-        Err(format!("Couldn't find for code: {}", code))
+        Ok(Unknown(code))
     }
 
     pub fn get_code(&self) -> u8 {
         match self {
             Self::Ipv4 => 4,
-            Self::Ipv6 => 6
+            Self::Ipv6 => 6,
+            Unknown(n) => *n
         }
     }
 
     pub fn to_string(&self) -> String {
         match self {
             Self::Ipv4 => "IPv4",
-            Self::Ipv6 => "IPv6"
+            Self::Ipv6 => "IPv6",
+            Unknown(_) => "Unknown"
         }.to_string()
     }
 }
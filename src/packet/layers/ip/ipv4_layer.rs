@@ -7,6 +7,7 @@ use crate::packet::layers::ip::inter::ip_versions::IpVersions;
 use crate::packet::layers::ip::tcp::tcp_layer::TcpLayer;
 use crate::packet::layers::ip::udp::udp_layer::UdpLayer;
 use crate::packet::layers::inter::layer::Layer;
+use crate::packet::layers::payload::payload_layer::PayloadLayer;
 
 pub const IPV4_HEADER_LEN: usize = 20;
 
@@ -190,23 +191,27 @@ impl Layer for Ipv4Layer {
             return None;
         }
 
-        let protocol = IpProtocols::from_code(buf[9]).unwrap();
+        let protocol = IpProtocols::from_code(buf[9]).ok()?;
 
+        let payload = &buf[IPV4_HEADER_LEN..];
+
+        //A HEADER CUT SHORT BY THE SNAPLEN OR WITHOUT A DISSECTOR IS KEPT AS RAW BYTES
         let data = match protocol {
             IpProtocols::HopByHop => None,
-            IpProtocols::Icmp => Some(IcmpLayer::from_bytes(&buf[IPV4_HEADER_LEN..])?.upcast()),
+            IpProtocols::Icmp => IcmpLayer::from_bytes(payload).map(IcmpLayer::upcast),
             IpProtocols::Igmp => None,
-            IpProtocols::Tcp => Some(TcpLayer::from_bytes(&buf[IPV4_HEADER_LEN..])?.upcast()),
-            IpProtocols::Udp => Some(UdpLayer::from_bytes(&buf[IPV4_HEADER_LEN..])?.upcast()),
+            IpProtocols::Tcp => TcpLayer::from_bytes(payload).map(TcpLayer::upcast),
+            IpProtocols::Udp => UdpLayer::from_bytes(payload).map(UdpLayer::upcast),
             IpProtocols::Ipv6 => None,
             IpProtocols::Gre => None,
             IpProtocols::Icmpv6 => None,
             IpProtocols::Ospf => None,
-            IpProtocols::Sps => None
-        };
+            IpProtocols::Sps => None,
+            IpProtocols::Unknown(_) => None
+        }.or_else(|| PayloadLayer::from_bytes(payload).map(PayloadLayer::upcast));
 
         Some(Self {
-            version: IpVersions::from_code((buf[0] >> 4) & 0x0F).unwrap(),
+            version: IpVersions::from_code((buf[0] >> 4) & 0x0F).ok()?,
             ihl: buf[0] & 0x0F,
             tos: buf[1],
             total_length: u16::from_be_bytes([buf[2], buf[3]]),
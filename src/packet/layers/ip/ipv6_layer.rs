@@ -6,6 +6,7 @@ use crate::packet::layers::ip::inter::ip_versions::IpVersions;
 use crate::packet::layers::ip::tcp::tcp_layer::TcpLayer;
 use crate::packet::layers::ip::udp::udp_layer::UdpLayer;
 use crate::packet::layers::inter::layer::Layer;
+use crate::packet::layers::payload::payload_layer::PayloadLayer;
 
 pub const IPV6_HEADER_LEN: usize = 40;
 
@@ -72,23 +73,27 @@ impl Layer for Ipv6Layer {
             return None;
         }
 
-        let next_header = IpProtocols::from_code(buf[6]).unwrap();
+        let next_header = IpProtocols::from_code(buf[6]).ok()?;
 
+        let payload = &buf[IPV6_HEADER_LEN..];
+
+        //A HEADER CUT SHORT BY THE SNAPLEN OR WITHOUT A DISSECTOR IS KEPT AS RAW BYTES
         let data = match next_header {
             IpProtocols::HopByHop => None,
             IpProtocols::Icmp => None,
             IpProtocols::Igmp => None,
-            IpProtocols::Tcp => Some(TcpLayer::from_bytes(&buf[IPV6_HEADER_LEN..])?.upcast()),
-            IpProtocols::Udp => Some(UdpLayer::from_bytes(&buf[IPV6_HEADER_LEN..])?.upcast()),
+            IpProtocols::Tcp => TcpLayer::from_bytes(payload).map(TcpLayer::upcast),
+            IpProtocols::Udp => UdpLayer::from_bytes(payload).map(UdpLayer::upcast),
             IpProtocols::Ipv6 => None,
             IpProtocols::Gre => None,
-            IpProtocols::Icmpv6 => Some(Icmpv6Layer::from_bytes(&buf[IPV6_HEADER_LEN..])?.upcast()),
+            IpProtocols::Icmpv6 => Icmpv6Layer::from_bytes(payload).map(Icmpv6Layer::upcast),
             IpProtocols::Ospf => None,
-            IpProtocols::Sps => None
-        };
+            IpProtocols::Sps => None,
+            IpProtocols::Unknown(_) => None
+        }.or_else(|| PayloadLayer::from_bytes(payload).map(PayloadLayer::upcast));
 
         Some(Self {
-            version: IpVersions::from_code((buf[0] >> 4) & 0x0F).unwrap(),
+            version: IpVersions::from_code((buf[0] >> 4) & 0x0F).ok()?,
             traffic_class: ((buf[0] & 0x0F) << 4) | (buf[1] >> 4),
             flow_label: ((buf[1] as u32 & 0x0F) << 16) | ((buf[2] as u32) << 8) | (buf[3] as u32),
             payload_length: u16::from_be_bytes([buf[4], buf[5]]),
@@ -5,6 +5,7 @@ use crate::packet::layers::ethernet_frame::inter::ethernet_types::EthernetTypes;
 use crate::packet::layers::ip::ipv4_layer::Ipv4Layer;
 use crate::packet::layers::ip::ipv6_layer::Ipv6Layer;
 use crate::packet::layers::inter::layer::Layer;
+use crate::packet::layers::payload::payload_layer::PayloadLayer;
 use crate::packet::layers::loop_frame::inter::loop_types::LoopTypes;
 use crate::packet::layers::sll2_frame::inter::packet_types::PacketTypes;
 
@@ -56,13 +57,16 @@ impl Layer for LoopFrame {
             return None;
         }
 
-        let _type = LoopTypes::from_code(u32::from_ne_bytes([buf[0], buf[1], buf[2], buf[3]])).unwrap();
+        let _type = LoopTypes::from_code(u32::from_ne_bytes([buf[0], buf[1], buf[2], buf[3]])).ok()?;
 
+        let payload = &buf[LOOP_FRAME_LENGTH..];
+
+        //A HEADER CUT SHORT BY THE SNAPLEN OR WITHOUT A DISSECTOR IS KEPT AS RAW BYTES
         let data = match _type {
-            LoopTypes::Ipv4 => Some(Ipv4Layer::from_bytes(&buf[4..]).unwrap().upcast()),
-            LoopTypes::Ipv6 | LoopTypes::Ipv6e2 | LoopTypes::Ipv6e3 => Some(Ipv6Layer::from_bytes(&buf[4..]).unwrap().upcast()),
+            LoopTypes::Ipv4 => Ipv4Layer::from_bytes(payload).map(Ipv4Layer::upcast),
+            LoopTypes::Ipv6 | LoopTypes::Ipv6e2 | LoopTypes::Ipv6e3 => Ipv6Layer::from_bytes(payload).map(Ipv6Layer::upcast),
             _ => None
-        };
+        }.or_else(|| PayloadLayer::from_bytes(payload).map(PayloadLayer::upcast));
 
         Some(Self {
             _type,
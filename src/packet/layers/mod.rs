@@ -4,3 +4,4 @@ pub mod ip;
 pub mod sll2_frame;
 pub mod loop_frame;
 pub mod raw_frame;
+pub mod payload;
@@ -0,0 +1 @@
+pub mod payload_layer;
@@ -0,0 +1,59 @@
+use std::any::Any;
+use crate::packet::layers::inter::layer::Layer;
+
+#[derive(Clone, Debug)]
+pub struct PayloadLayer {
+    data: Vec<u8>
+}
+
+impl PayloadLayer {
+
+    pub fn new(data: &[u8]) -> Self {
+        Self {
+            data: data.to_vec()
+        }
+    }
+
+    pub fn set_data(&mut self, data: &[u8]) {
+        self.data = data.to_vec();
+    }
+
+    pub fn get_data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl Layer for PayloadLayer {
+
+    fn from_bytes(buf: &[u8]) -> Option<Self> {
+        if buf.is_empty() {
+            return None;
+        }
+
+        Some(Self::new(buf))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn compute_length(&mut self) -> usize {
+        self.data.len()
+    }
+
+    fn dyn_clone(&self) -> Box<dyn Layer> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
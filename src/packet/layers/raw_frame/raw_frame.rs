@@ -2,6 +2,7 @@ use std::any::Any;
 use crate::packet::layers::ip::ipv4_layer::Ipv4Layer;
 use crate::packet::layers::ip::ipv6_layer::Ipv6Layer;
 use crate::packet::layers::inter::layer::Layer;
+use crate::packet::layers::payload::payload_layer::PayloadLayer;
 use crate::packet::layers::ip::inter::ip_versions::IpVersions;
 
 #[derive(Clone, Debug)]
@@ -50,12 +51,14 @@ impl Layer for RawFrame {
             return None;
         }
 
-        let version = IpVersions::from_code((buf[0] >> 4) & 0x0F).unwrap();
+        let version = IpVersions::from_code((buf[0] >> 4) & 0x0F).ok()?;
 
+        //A HEADER CUT SHORT BY THE SNAPLEN OR WITHOUT A DISSECTOR IS KEPT AS RAW BYTES
         let data = match version {
-            IpVersions::Ipv4 => Some(Ipv4Layer::from_bytes(buf).unwrap().upcast()),
-            IpVersions::Ipv6 => Some(Ipv6Layer::from_bytes(buf).unwrap().upcast())
-        };
+            IpVersions::Ipv4 => Ipv4Layer::from_bytes(buf).map(Ipv4Layer::upcast),
+            IpVersions::Ipv6 => Ipv6Layer::from_bytes(buf).map(Ipv6Layer::upcast),
+            IpVersions::Unknown(_) => None
+        }.or_else(|| PayloadLayer::from_bytes(buf).map(PayloadLayer::upcast));
 
         Some(Self {
             version,
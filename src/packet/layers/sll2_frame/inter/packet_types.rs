@@ -4,7 +4,8 @@ pub enum PacketTypes {
     Broadcast,
     Multicast,
     OtherHost,
-    OutGoing
+    OutGoing,
+    Unknown(u8)
 }
 
 impl PacketTypes {
@@ -16,7 +17,7 @@ impl PacketTypes {
             }
         }
 
-        Err(format!("Couldn't find for code: {}", code))
+        Ok(Self::Unknown(code))
     }
 
     pub fn get_code(&self) -> u8 {
@@ -25,7 +26,8 @@ impl PacketTypes {
             Self::Broadcast => 1,
             Self::Multicast => 2,
             Self::OtherHost => 3,
-            Self::OutGoing => 4
+            Self::OutGoing => 4,
+            Self::Unknown(code) => *code
         }
     }
 
@@ -35,7 +37,8 @@ impl PacketTypes {
             Self::Broadcast => "PACKET_BROADCAST",
             Self::Multicast => "PACKET_MULTICAST",
             Self::OtherHost => "PACKET_OTHERHOST",
-            Self::OutGoing => "PACKET_OUTGOING"
+            Self::OutGoing => "PACKET_OUTGOING",
+            Self::Unknown(_) => "Unknown"
         }.to_string()
     }
 }
@@ -4,6 +4,7 @@ use crate::packet::layers::ethernet_frame::inter::ethernet_types::EthernetTypes;
 use crate::packet::layers::ip::ipv4_layer::Ipv4Layer;
 use crate::packet::layers::ip::ipv6_layer::Ipv6Layer;
 use crate::packet::layers::inter::layer::Layer;
+use crate::packet::layers::payload::payload_layer::PayloadLayer;
 use crate::packet::layers::sll2_frame::inter::packet_types::PacketTypes;
 
 pub const SLL2_FRAME_LEN: usize = 20;
@@ -14,6 +15,7 @@ pub struct Sll2Frame {
     reserved: u16,
     if_index: i32,
     data_link_type: DataLinkTypes,
+    hardware_type: u16,
     packet_type: PacketTypes,
     address_length: u8,
     address: [u8; 8],
@@ -29,6 +31,7 @@ impl Sll2Frame {
             reserved: 0,
             if_index: 1,
             data_link_type: DataLinkTypes::Null,
+            hardware_type: 0,
             packet_type,
             address_length,
             address,
@@ -59,6 +62,15 @@ impl Sll2Frame {
 
     pub fn set_data_link_type(&mut self, data_link_type: DataLinkTypes) {
         self.data_link_type = data_link_type;
+        self.hardware_type = data_link_type.get_code() as u16;
+    }
+
+    pub fn get_hardware_type(&self) -> u16 {
+        self.hardware_type
+    }
+
+    pub fn set_hardware_type(&mut self, hardware_type: u16) {
+        self.hardware_type = hardware_type;
     }
 
     pub fn get_packet_type(&self) -> PacketTypes {
@@ -106,20 +118,27 @@ impl Layer for Sll2Frame {
             return None;
         }
 
-        let protocol = EthernetTypes::from_code(u16::from_be_bytes(buf[0..2].try_into().unwrap())).unwrap();
+        let protocol = EthernetTypes::from_code(u16::from_be_bytes(buf[0..2].try_into().unwrap())).ok()?;
 
+        let payload = &buf[SLL2_FRAME_LEN..];
+
+        //A HEADER CUT SHORT BY THE SNAPLEN OR WITHOUT A DISSECTOR IS KEPT AS RAW BYTES
         let data = match protocol {
-            EthernetTypes::Ipv4 => Some(Ipv4Layer::from_bytes(&buf[SLL2_FRAME_LEN..])?.upcast()),
-            EthernetTypes::Ipv6 => Some(Ipv6Layer::from_bytes(&buf[SLL2_FRAME_LEN..])?.upcast()),
+            EthernetTypes::Ipv4 => Ipv4Layer::from_bytes(payload).map(Ipv4Layer::upcast),
+            EthernetTypes::Ipv6 => Ipv6Layer::from_bytes(payload).map(Ipv6Layer::upcast),
             _ => None
-        };
+        }.or_else(|| PayloadLayer::from_bytes(payload).map(PayloadLayer::upcast));
+
+        //ARPHRD TYPE, LOOPBACK / TUNNELS / ETC HAVE NO MATCHING DATA LINK TYPE
+        let hardware_type = u16::from_be_bytes(buf[8..10].try_into().unwrap());
 
         Some(Self {
             protocol,
             reserved: u16::from_be_bytes(buf[2..4].try_into().unwrap()),
             if_index: i32::from_be_bytes(buf[4..8].try_into().unwrap()),
-            data_link_type: DataLinkTypes::from_code(u16::from_be_bytes(buf[8..10].try_into().unwrap()) as u32).unwrap(),
-            packet_type: PacketTypes::from_code(buf[10]).unwrap(),
+            data_link_type: DataLinkTypes::from_code(hardware_type as u32).unwrap_or(DataLinkTypes::Null),
+            hardware_type,
+            packet_type: PacketTypes::from_code(buf[10]).ok()?,
             address_length: buf[11],
             address: buf[12..20].try_into().unwrap(),
             data,
@@ -132,7 +151,7 @@ impl Layer for Sll2Frame {
 
         buf.splice(0..2, self.protocol.get_code().to_be_bytes());
         buf.splice(4..8, self.if_index.to_be_bytes());
-        buf.splice(8..10, self.data_link_type.get_code().to_be_bytes());
+        buf.splice(8..10, self.hardware_type.to_be_bytes());
         buf[10] = self.packet_type.get_code();
         buf[11] = self.address_length;
         buf.splice(12..20, self.address);
@@ -1,10 +1,14 @@
+use std::io;
 use crate::utils::data_link_types::DataLinkTypes;
+use crate::utils::checksum_statuses::ChecksumStatuses;
+use crate::utils::timestamp_sources::TimestampSources;
 use crate::packet::layers::ethernet_frame::ethernet_frame::EthernetFrame;
 use crate::packet::layers::ip::ipv4_layer::Ipv4Layer;
 use crate::packet::layers::ip::ipv6_layer::Ipv6Layer;
 use crate::packet::layers::inter::layer::Layer;
 use crate::packet::layers::loop_frame::loop_frame::LoopFrame;
 use crate::packet::layers::raw_frame::raw_frame::RawFrame;
+use crate::packet::layers::sll2_frame::inter::packet_types::PacketTypes;
 use crate::packet::layers::sll2_frame::sll2_frame::Sll2Frame;
 
 #[derive(Debug, Clone)]
@@ -12,36 +16,34 @@ pub struct Packet {
     data_link_type: DataLinkTypes,
     frame: Box<dyn Layer>,
     frame_time: u128,
-    length: usize
+    timestamp_source: TimestampSources,
+    packet_type: Option<PacketTypes>,
+    checksum_status: ChecksumStatuses,
+    length: usize,
+    original_length: usize
 }
 
 impl Packet {
 
-    pub fn new(data_link_type: DataLinkTypes, frame_time: u128, data: &[u8]) -> Self {
+    pub fn new(data_link_type: DataLinkTypes, frame_time: u128, data: &[u8]) -> io::Result<Self> {
         let frame = match data_link_type {
-            DataLinkTypes::En10mb => {
-                EthernetFrame::from_bytes(data).unwrap().upcast()
-            }
-            DataLinkTypes::Loop => {
-                LoopFrame::from_bytes(data).unwrap().upcast()
-            }
-            DataLinkTypes::Raw => {
-                RawFrame::from_bytes(data).unwrap().upcast()
-            }
-            DataLinkTypes::Sll2 => {
-                Sll2Frame::from_bytes(data).unwrap().upcast()
-            }
-            _ => {
-                todo!()
-            }
-        };
-
-        Self {
+            DataLinkTypes::En10mb => EthernetFrame::from_bytes(data).map(EthernetFrame::upcast),
+            DataLinkTypes::Loop => LoopFrame::from_bytes(data).map(LoopFrame::upcast),
+            DataLinkTypes::Raw => RawFrame::from_bytes(data).map(RawFrame::upcast),
+            DataLinkTypes::Sll2 => Sll2Frame::from_bytes(data).map(Sll2Frame::upcast),
+            _ => return Err(io::Error::new(io::ErrorKind::Unsupported, format!("Unsupported data link type {}", data_link_type.to_string())))
+        }.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("Truncated or malformed {} frame of {} bytes", data_link_type.to_string(), data.len())))?;
+
+        Ok(Self {
             data_link_type,
             frame,
             frame_time,
-            length: data.len()
-        }
+            timestamp_source: TimestampSources::User,
+            packet_type: None,
+            checksum_status: ChecksumStatuses::Unknown,
+            length: data.len(),
+            original_length: data.len()
+        })
     }
 
     pub fn set_data_link_type(&mut self, data_link_type: DataLinkTypes) {
@@ -54,6 +56,7 @@ impl Packet {
 
     pub fn set_frame(&mut self, frame: Box<dyn Layer>) {
         self.length = frame.len();
+        self.original_length = self.length;
         self.frame = frame;
     }
 
@@ -73,6 +76,42 @@ impl Packet {
         self.frame_time
     }
 
+    pub fn set_timestamp_source(&mut self, timestamp_source: TimestampSources) {
+        self.timestamp_source = timestamp_source;
+    }
+
+    pub fn get_timestamp_source(&self) -> TimestampSources {
+        self.timestamp_source
+    }
+
+    pub fn set_packet_type(&mut self, packet_type: Option<PacketTypes>) {
+        self.packet_type = packet_type;
+    }
+
+    pub fn get_packet_type(&self) -> Option<PacketTypes> {
+        self.packet_type
+    }
+
+    pub fn set_checksum_status(&mut self, checksum_status: ChecksumStatuses) {
+        self.checksum_status = checksum_status;
+    }
+
+    pub fn get_checksum_status(&self) -> ChecksumStatuses {
+        self.checksum_status
+    }
+
+    pub fn get_captured_length(&self) -> usize {
+        self.length
+    }
+
+    pub fn set_original_length(&mut self, original_length: usize) {
+        self.original_length = original_length;
+    }
+
+    pub fn get_original_length(&self) -> usize {
+        self.original_length
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         self.frame.to_bytes()
     }
@@ -1,3 +1,4 @@
 pub mod inter;
 pub mod pcap;
 pub mod pcapng;
+pub mod pcap_replay_source;
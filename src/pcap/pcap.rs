@@ -3,7 +3,9 @@ use std::io;
 use std::io::{Read, Write};
 use std::vec::IntoIter;
 use crate::utils::data_link_types::DataLinkTypes;
+use crate::filter::bpf_vm::BpfVm;
 use crate::packet::packet::Packet;
+use crate::utils::sock_filter::SockFilter;
 
 pub const PCAP_HEADER_LEN: usize = 24;
 pub const MAGIC_NUMBER: u32 = 0xA1B2C3D4;
@@ -71,7 +73,9 @@ impl Pcap {
             let mut buf = vec![0u8; captured_len as usize];
             file.read_exact(&mut buf)?;
 
-            packets.push(Packet::new(data_link_type, frame_time, &buf));
+            let mut packet = Packet::new(data_link_type, frame_time, &buf)?;
+            packet.set_original_length(original_len as usize);
+            packets.push(packet);
         }
 
         Ok(Self {
@@ -100,10 +104,11 @@ impl Pcap {
             let (timestamp_sec, timestamp_usec) = Self::split_timestamp(packet.get_frame_time());
             file.write_all(&timestamp_sec.to_le_bytes())?;
             file.write_all(&timestamp_usec.to_le_bytes())?;
-            file.write_all(&packet.len().to_le_bytes())?;
-            file.write_all(&packet.len().to_le_bytes())?;
+            let data = packet.to_bytes();
+            file.write_all(&(data.len() as u32).to_le_bytes())?;
+            file.write_all(&(packet.get_original_length().max(data.len()) as u32).to_le_bytes())?;
 
-            file.write_all(&packet.to_bytes())?;
+            file.write_all(&data)?;
         }
 
         Ok(())
@@ -138,6 +143,10 @@ impl Pcap {
         self.payload_length as usize
     }
 
+    pub fn set_data_link_type(&mut self, data_link_type: DataLinkTypes) {
+        self.data_link_type = data_link_type;
+    }
+
     pub fn get_data_link_type(&self) -> DataLinkTypes {
         self.data_link_type
     }
@@ -162,6 +171,16 @@ impl Pcap {
         self.packets.len()
     }
 
+    pub fn filter(&self, program: &[SockFilter]) -> io::Result<Vec<Packet>> {
+        let vm = BpfVm::new(program)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        Ok(self.packets.iter()
+            .filter(|packet| vm.run_with_length(&packet.to_bytes(), packet.get_original_length() as u32) > 0)
+            .cloned()
+            .collect())
+    }
+
     fn split_timestamp(timestamp_ns: u128) -> (u32, u32) {
         let timestamp_sec = (timestamp_ns / 1_000_000_000) as u32;
         let timestamp_usec = ((timestamp_ns % 1_000_000_000) / 1_000) as u32;
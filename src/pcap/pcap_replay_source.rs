@@ -0,0 +1,155 @@
+use std::cell::{Cell, RefCell};
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::filter::bpf_vm::BpfVm;
+use crate::inter::capture_source::CaptureSource;
+use crate::packet::packet::Packet;
+use crate::pcap::pcap::Pcap;
+use crate::pcap::pcapng::PcapNg;
+use crate::utils::capture_stats::CaptureStats;
+use crate::utils::data_link_types::DataLinkTypes;
+use crate::utils::sock_filter::SockFilter;
+
+#[derive(Debug)]
+pub struct PcapReplaySource {
+    data_link_type: DataLinkTypes,
+    packets: Vec<Packet>,
+    position: Cell<usize>,
+    received: Cell<u64>,
+    original_timing: bool,
+    started: Cell<Option<(Instant, u128)>>,
+    filter: RefCell<Option<BpfVm>>,
+    sent: RefCell<Vec<Packet>>
+}
+
+impl PcapReplaySource {
+
+    pub fn new(data_link_type: DataLinkTypes, packets: Vec<Packet>) -> Self {
+        Self {
+            data_link_type,
+            packets,
+            position: Cell::new(0),
+            received: Cell::new(0),
+            original_timing: false,
+            started: Cell::new(None),
+            filter: RefCell::new(None),
+            sent: RefCell::new(Vec::new())
+        }
+    }
+
+    pub fn from_pcap(pcap: Pcap) -> Self {
+        let data_link_type = pcap.get_data_link_type();
+        Self::new(data_link_type, pcap.into_iter().collect())
+    }
+
+    pub fn from_pcapng(pcapng: PcapNg) -> Self {
+        //PCAPNG CARRIES THE LINK TYPE PER INTERFACE, THE SOURCE REPORTS THE FIRST ONE
+        let data_link_type = pcapng.get_data_link_type();
+        Self::new(data_link_type, pcapng.into_iter().collect())
+    }
+
+    pub fn set_original_timing(&mut self, original_timing: bool) {
+        self.original_timing = original_timing;
+    }
+
+    pub fn is_original_timing(&self) -> bool {
+        self.original_timing
+    }
+
+    pub fn get_sent(&self) -> Vec<Packet> {
+        self.sent.borrow().clone()
+    }
+
+    pub fn get_position(&self) -> usize {
+        self.position.get()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.packets.len() - self.position.get()
+    }
+
+    fn next(&self, block: bool) -> io::Result<Packet> {
+        loop {
+            let Some(packet) = self.packets.get(self.position.get()) else {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "End of capture file"));
+            };
+
+            if self.original_timing {
+                //FIRST PACKET PINS THE FILE CLOCK TO OURS, THE REST KEEP THEIR ORIGINAL GAPS
+                let (start, first) = match self.started.get() {
+                    Some(started) => started,
+                    None => {
+                        let started = (Instant::now(), packet.get_frame_time());
+                        self.started.set(Some(started));
+                        started
+                    }
+                };
+
+                let offset = packet.get_frame_time().saturating_sub(first);
+                let due = start + Duration::from_nanos(offset.min(u64::MAX as u128) as u64);
+                let now = Instant::now();
+
+                if due > now {
+                    if !block {
+                        return Err(io::Error::new(io::ErrorKind::WouldBlock, "No data available"));
+                    }
+
+                    thread::sleep(due - now);
+                }
+            }
+
+            self.position.set(self.position.get() + 1);
+
+            if let Some(ref vm) = *self.filter.borrow() {
+                if vm.run_with_length(&packet.to_bytes(), packet.get_original_length() as u32) == 0 {
+                    continue;
+                }
+            }
+
+            self.received.set(self.received.get() + 1);
+            return Ok(packet.clone());
+        }
+    }
+}
+
+impl CaptureSource for PcapReplaySource {
+
+    fn open(&mut self) -> io::Result<()> {
+        //REWIND SO THE SAME SOURCE CAN BE PLAYED AGAIN
+        self.position.set(0);
+        self.received.set(0);
+        self.started.set(None);
+        Ok(())
+    }
+
+    fn recv(&self) -> io::Result<Packet> {
+        self.next(true)
+    }
+
+    fn try_recv(&self) -> io::Result<Packet> {
+        self.next(false)
+    }
+
+    fn send(&self, packet: Packet) -> io::Result<usize> {
+        let length = packet.get_captured_length();
+        self.sent.borrow_mut().push(packet);
+        Ok(length)
+    }
+
+    fn stats(&self) -> io::Result<CaptureStats> {
+        Ok(CaptureStats::new(self.received.get(), 0, 0, 0, 0, 0, 0))
+    }
+
+    fn set_filter(&self, filter: &[SockFilter]) -> io::Result<()> {
+        let vm = BpfVm::new(filter)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        *self.filter.borrow_mut() = Some(vm);
+        Ok(())
+    }
+
+    fn get_data_link_type(&self) -> DataLinkTypes {
+        self.data_link_type
+    }
+}
@@ -1,15 +1,21 @@
 use std::collections::HashMap;
 use std::fs::File;
 use std::io;
-use std::io::{Read, Write};
+use std::io::Read;
 use std::vec::IntoIter;
 use crate::utils::data_link_types::DataLinkTypes;
+use crate::filter::bpf_vm::BpfVm;
 use crate::packet::packet::Packet;
+use crate::utils::sock_filter::SockFilter;
 use crate::pcap::inter::pcapng_options::PcapNgOptions;
 
 pub const PCAP_HEADER_LEN: usize = 24;
 pub const MAGIC_NUMBER: u32 = 0x0A0D0D0A;
 pub const BYTE_ORDER_MAGIC: u32 = 0x1A2B3C4D; // Indicates Little Endian
+pub const INTERFACE_DESCRIPTION_BLOCK: u32 = 0x00000001;
+pub const SIMPLE_PACKET_BLOCK: u32 = 0x00000003;
+pub const ENHANCED_PACKET_BLOCK: u32 = 0x00000006;
+pub const IF_TSRESOL: u16 = 9;
 
 #[derive(Debug, Clone)]
 pub struct PcapNg {
@@ -40,8 +46,8 @@ impl PcapNg {
 
     pub fn from_file(file_path: &str) -> io::Result<Self> {
         let mut file = File::open(file_path)?;
-        let mut buf = [0u8; 24];
-        file.read_exact(&mut buf)?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
 
         if buf.len() < PCAP_HEADER_LEN {
             return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid header length in pcap file"));
@@ -51,59 +57,217 @@ impl PcapNg {
             return Err(io::Error::new(io::ErrorKind::InvalidData, "Magic number mismatch"));
         }
 
-        let block_length = u32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]);
-        if block_length < 24 {
-            return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid SHB block length"));
+        let mut pcapng = Self {
+            byte_order: true,
+            version_major: 0,
+            version_minor: 0,
+            section_length: -1,
+            options: HashMap::new(),
+            data_link_type: DataLinkTypes::Null,
+            packets: Vec::new()
+        };
+
+        //LINK TYPE AND TIMESTAMP RESOLUTION OF EACH INTERFACE IN THE CURRENT SECTION
+        let mut interfaces: Vec<(DataLinkTypes, u32, u8)> = Vec::new();
+        let mut off = 0;
+
+        while off < buf.len() {
+            if buf.len() - off < 12 {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "Truncated block header in pcapng file"));
+            }
+
+            let block_type = u32::from_le_bytes([buf[off], buf[off+1], buf[off+2], buf[off+3]]);
+
+            //EVERY SECTION DECLARES ITS OWN BYTE ORDER
+            if block_type == MAGIC_NUMBER {
+                pcapng.byte_order = u32::from_le_bytes([buf[off+8], buf[off+9], buf[off+10], buf[off+11]]) == BYTE_ORDER_MAGIC;
+            }
+
+            let block_length = pcapng.read_u32(&buf, off + 4) as usize;
+            if block_length < 12 || !block_length.is_multiple_of(4) || block_length > buf.len() - off {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, format!("Invalid block length {} in pcapng file", block_length)));
+            }
+
+            let block = &buf[off..off + block_length - 4];
+
+            match block_type {
+                MAGIC_NUMBER => {
+                    if block.len() < PCAP_HEADER_LEN {
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid SHB block length"));
+                    }
+
+                    pcapng.version_major = pcapng.read_u16(block, 12);
+                    pcapng.version_minor = pcapng.read_u16(block, 14);
+                    pcapng.section_length = pcapng.read_u64(block, 16) as i64;
+
+                    for (code, value) in pcapng.read_options(&block[PCAP_HEADER_LEN..]) {
+                        if let Ok(option) = PcapNgOptions::from_code(code) {
+                            pcapng.options.insert(option, String::from_utf8_lossy(value).to_string());
+                        }
+                    }
+
+                    interfaces.clear();
+                }
+                INTERFACE_DESCRIPTION_BLOCK => {
+                    if block.len() < 16 {
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid IDB block length"));
+                    }
+
+                    let data_link_type = DataLinkTypes::from_code(pcapng.read_u16(block, 8) as u32)
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+                    //MICROSECONDS UNLESS if_tsresol SAYS OTHERWISE
+                    let resolution = pcapng.read_options(&block[16..]).into_iter()
+                        .find(|(code, value)| *code == IF_TSRESOL && value.len() == 1)
+                        .map(|(_, value)| value[0])
+                        .unwrap_or(6);
+
+                    if pcapng.packets.is_empty() && interfaces.is_empty() {
+                        pcapng.data_link_type = data_link_type;
+                    }
+
+                    interfaces.push((data_link_type, pcapng.read_u32(block, 12), resolution));
+                }
+                ENHANCED_PACKET_BLOCK => {
+                    if block.len() < 28 {
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid EPB block length"));
+                    }
+
+                    let &(data_link_type, _, resolution) = interfaces.get(pcapng.read_u32(block, 8) as usize)
+                        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "EPB references an undeclared interface"))?;
+
+                    let timestamp = ((pcapng.read_u32(block, 12) as u64) << 32) | pcapng.read_u32(block, 16) as u64;
+                    let captured_length = pcapng.read_u32(block, 20) as usize;
+                    let original_length = pcapng.read_u32(block, 24) as usize;
+
+                    let data = block.get(28..28 + captured_length)
+                        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "EPB captured length exceeds block"))?;
+
+                    let mut packet = Packet::new(data_link_type, Self::to_nanos(timestamp, resolution), data)?;
+                    packet.set_original_length(original_length);
+                    pcapng.packets.push(packet);
+                }
+                SIMPLE_PACKET_BLOCK => {
+                    if block.len() < 12 {
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid SPB block length"));
+                    }
+
+                    let &(data_link_type, snaplen, _) = interfaces.first()
+                        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "SPB without an interface"))?;
+
+                    //NO CAPTURED LENGTH, IT IS WHAT FITS UNDER THE SNAPLEN
+                    let original_length = pcapng.read_u32(block, 8) as usize;
+                    let mut captured_length = original_length.min(block.len() - 12);
+                    if snaplen > 0 {
+                        captured_length = captured_length.min(snaplen as usize);
+                    }
+
+                    let mut packet = Packet::new(data_link_type, 0, &block[12..12 + captured_length])?;
+                    packet.set_original_length(original_length);
+                    pcapng.packets.push(packet);
+                }
+                _ => {}
+            }
+
+            off += block_length;
         }
 
-        let byte_order_magic = u32::from_le_bytes([buf[8], buf[9], buf[10], buf[11]]);
-        let byte_order = byte_order_magic == BYTE_ORDER_MAGIC;
-
-        let (version_major, version_minor, section_length) = if byte_order {
-            (
-                u16::from_le_bytes([buf[12], buf[13]]),
-                u16::from_le_bytes([buf[14], buf[15]]),
-                i64::from_le_bytes([buf[16], buf[17], buf[18], buf[19], buf[20], buf[21], buf[22], buf[23]]),
-            )
-        } else {
-            (
-                u16::from_be_bytes([buf[12], buf[13]]),
-                u16::from_be_bytes([buf[14], buf[15]]),
-                i64::from_be_bytes([buf[16], buf[17], buf[18], buf[19], buf[20], buf[21], buf[22], buf[23]]),
-            )
-        };
+        Ok(pcapng)
+    }
 
-        let options_length = (block_length - 24 - 4) as usize;
-        let mut buf = vec![0u8; options_length];
-        file.read_exact(&mut buf)?;
+    pub fn get_version_major(&self) -> u16 {
+        self.version_major
+    }
 
-        let mut options = HashMap::new();
+    pub fn get_version_minor(&self) -> u16 {
+        self.version_minor
+    }
+
+    pub fn get_section_length(&self) -> i64 {
+        self.section_length
+    }
+
+    pub fn get_option(&self, option: PcapNgOptions) -> Option<&String> {
+        self.options.get(&option)
+    }
+
+    pub fn get_data_link_type(&self) -> DataLinkTypes {
+        self.data_link_type
+    }
+
+    pub fn get_packet(&self, index: usize) -> &Packet {
+        &self.packets[index]
+    }
+
+    pub fn get_packets(&self) -> Vec<Packet> {
+        self.packets.clone()
+    }
+
+    pub fn total_packets(&self) -> usize {
+        self.packets.len()
+    }
+
+    pub fn filter(&self, program: &[SockFilter]) -> io::Result<Vec<Packet>> {
+        let vm = BpfVm::new(program)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        Ok(self.packets.iter()
+            .filter(|packet| vm.run_with_length(&packet.to_bytes(), packet.get_original_length() as u32) > 0)
+            .cloned()
+            .collect())
+    }
+
+    fn read_u16(&self, buf: &[u8], off: usize) -> u16 {
+        let bytes = [buf[off], buf[off+1]];
+        if self.byte_order { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) }
+    }
+
+    fn read_u32(&self, buf: &[u8], off: usize) -> u32 {
+        let bytes = [buf[off], buf[off+1], buf[off+2], buf[off+3]];
+        if self.byte_order { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) }
+    }
+
+    fn read_u64(&self, buf: &[u8], off: usize) -> u64 {
+        let bytes = buf[off..off + 8].try_into().unwrap();
+        if self.byte_order { u64::from_le_bytes(bytes) } else { u64::from_be_bytes(bytes) }
+    }
+
+    fn read_options<'a>(&self, buf: &'a [u8]) -> Vec<(u16, &'a [u8])> {
+        let mut options = Vec::new();
         let mut off = 0;
 
-        while off < options_length {
-            let opt = PcapNgOptions::from_code(u16::from_le_bytes([buf[off], buf[off+1]]))
-                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-            if opt == PcapNgOptions::End {
+        while off + 4 <= buf.len() {
+            let code = self.read_u16(buf, off);
+            if code == PcapNgOptions::End.get_code() {
                 break;
             }
 
-            let length = u16::from_le_bytes([buf[off+2], buf[off+3]]) as usize;
+            let length = self.read_u16(buf, off + 2) as usize;
+            let Some(value) = buf.get(off + 4..off + 4 + length) else {
+                break;
+            };
 
-            options.insert(opt, String::from_utf8_lossy(&buf[off + 4..off + 4 + length]).to_string());
+            options.push((code, value));
 
             let padding = (4 - ((length + 4) % 4)) % 4;
             off += padding + length + 4;
         }
 
-        Ok(Self {
-            byte_order,
-            version_major,
-            version_minor,
-            section_length,
-            options,
-            data_link_type: DataLinkTypes::Null,
-            packets: Vec::new()
-        })
+        options
+    }
+
+    fn to_nanos(timestamp: u64, resolution: u8) -> u128 {
+        let exponent = (resolution & 0x7F) as u32;
+
+        //HIGH BIT SET MEANS A POWER OF TWO RESOLUTION, OTHERWISE A POWER OF TEN
+        if resolution & 0x80 != 0 {
+            return ((timestamp as u128) * 1_000_000_000) >> exponent.min(127);
+        }
+
+        match exponent {
+            0..=9 => timestamp as u128 * 10u128.pow(9 - exponent),
+            _ => timestamp as u128 / 10u128.pow((exponent - 9).min(38))
+        }
     }
 }
 
@@ -0,0 +1,17 @@
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum CaptureDirections {
+    In,
+    Out,
+    InOut
+}
+
+impl CaptureDirections {
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::In => "In",
+            Self::Out => "Out",
+            Self::InOut => "In Out"
+        }.to_string()
+    }
+}
@@ -0,0 +1,53 @@
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct CaptureStats {
+    received: u64,
+    dropped: u64,
+    freeze_queue_count: u64,
+    interface_received: u64,
+    interface_dropped: u64,
+    interface_errors: u64,
+    interface_missed: u64
+}
+
+impl CaptureStats {
+
+    pub fn new(received: u64, dropped: u64, freeze_queue_count: u64, interface_received: u64, interface_dropped: u64, interface_errors: u64, interface_missed: u64) -> Self {
+        Self {
+            received,
+            dropped,
+            freeze_queue_count,
+            interface_received,
+            interface_dropped,
+            interface_errors,
+            interface_missed
+        }
+    }
+
+    pub fn get_received(&self) -> u64 {
+        self.received
+    }
+
+    pub fn get_dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    pub fn get_freeze_queue_count(&self) -> u64 {
+        self.freeze_queue_count
+    }
+
+    pub fn get_interface_received(&self) -> u64 {
+        self.interface_received
+    }
+
+    pub fn get_interface_dropped(&self) -> u64 {
+        self.interface_dropped
+    }
+
+    pub fn get_interface_errors(&self) -> u64 {
+        self.interface_errors
+    }
+
+    pub fn get_interface_missed(&self) -> u64 {
+        self.interface_missed
+    }
+}
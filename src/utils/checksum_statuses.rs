@@ -0,0 +1,17 @@
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum ChecksumStatuses {
+    Unknown,
+    NotReady,
+    Valid
+}
+
+impl ChecksumStatuses {
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Unknown => "Unknown",
+            Self::NotReady => "Not Ready",
+            Self::Valid => "Valid"
+        }.to_string()
+    }
+}
@@ -0,0 +1,37 @@
+use std::net::IpAddr;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct DeviceAddress {
+    address: IpAddr,
+    prefix_length: u8,
+    broadcast: Option<IpAddr>,
+    peer: Option<IpAddr>
+}
+
+impl DeviceAddress {
+
+    pub fn new(address: IpAddr, prefix_length: u8, broadcast: Option<IpAddr>, peer: Option<IpAddr>) -> Self {
+        Self {
+            address,
+            prefix_length,
+            broadcast,
+            peer
+        }
+    }
+
+    pub fn get_address(&self) -> IpAddr {
+        self.address
+    }
+
+    pub fn get_prefix_length(&self) -> u8 {
+        self.prefix_length
+    }
+
+    pub fn get_broadcast(&self) -> Option<IpAddr> {
+        self.broadcast
+    }
+
+    pub fn get_peer(&self) -> Option<IpAddr> {
+        self.peer
+    }
+}
@@ -0,0 +1,49 @@
+use crate::utils::duplex_modes::DuplexModes;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DeviceDetails {
+    speed: Option<u32>,
+    duplex: DuplexModes,
+    carrier: bool,
+    driver: Option<String>,
+    kind: Option<String>,
+    _virtual: bool
+}
+
+impl DeviceDetails {
+
+    pub fn new(speed: Option<u32>, duplex: DuplexModes, carrier: bool, driver: Option<String>, kind: Option<String>, _virtual: bool) -> Self {
+        Self {
+            speed,
+            duplex,
+            carrier,
+            driver,
+            kind,
+            _virtual
+        }
+    }
+
+    pub fn get_speed(&self) -> Option<u32> {
+        self.speed
+    }
+
+    pub fn get_duplex(&self) -> DuplexModes {
+        self.duplex
+    }
+
+    pub fn has_carrier(&self) -> bool {
+        self.carrier
+    }
+
+    pub fn get_driver(&self) -> Option<String> {
+        self.driver.clone()
+    }
+
+    pub fn get_kind(&self) -> Option<String> {
+        self.kind.clone()
+    }
+
+    pub fn is_virtual(&self) -> bool {
+        self._virtual
+    }
+}
@@ -0,0 +1,91 @@
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct DeviceCounters {
+    bytes: u64,
+    packets: u64,
+    errors: u64,
+    dropped: u64
+}
+
+impl DeviceCounters {
+
+    pub fn new(bytes: u64, packets: u64, errors: u64, dropped: u64) -> Self {
+        Self {
+            bytes,
+            packets,
+            errors,
+            dropped
+        }
+    }
+
+    pub fn get_bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    pub fn get_packets(&self) -> u64 {
+        self.packets
+    }
+
+    pub fn get_errors(&self) -> u64 {
+        self.errors
+    }
+
+    pub fn get_dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct DeviceStats {
+    rx: DeviceCounters,
+    tx: DeviceCounters
+}
+
+impl DeviceStats {
+
+    pub fn new(rx: DeviceCounters, tx: DeviceCounters) -> Self {
+        Self {
+            rx,
+            tx
+        }
+    }
+
+    pub fn get_rx(&self) -> DeviceCounters {
+        self.rx
+    }
+
+    pub fn get_tx(&self) -> DeviceCounters {
+        self.tx
+    }
+
+    pub fn get_rx_bytes(&self) -> u64 {
+        self.rx.bytes
+    }
+
+    pub fn get_rx_packets(&self) -> u64 {
+        self.rx.packets
+    }
+
+    pub fn get_rx_errors(&self) -> u64 {
+        self.rx.errors
+    }
+
+    pub fn get_rx_dropped(&self) -> u64 {
+        self.rx.dropped
+    }
+
+    pub fn get_tx_bytes(&self) -> u64 {
+        self.tx.bytes
+    }
+
+    pub fn get_tx_packets(&self) -> u64 {
+        self.tx.packets
+    }
+
+    pub fn get_tx_errors(&self) -> u64 {
+        self.tx.errors
+    }
+
+    pub fn get_tx_dropped(&self) -> u64 {
+        self.tx.dropped
+    }
+}
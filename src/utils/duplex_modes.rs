@@ -0,0 +1,17 @@
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum DuplexModes {
+    Half,
+    Full,
+    Unknown
+}
+
+impl DuplexModes {
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Half => "Half",
+            Self::Full => "Full",
+            Self::Unknown => "Unknown"
+        }.to_string()
+    }
+}
@@ -1,2 +1,12 @@
 pub mod interface_flags;
 pub mod data_link_types;
+pub mod capture_directions;
+pub mod capture_stats;
+pub mod checksum_statuses;
+pub mod device_address;
+pub mod device_details;
+pub mod device_stats;
+pub mod duplex_modes;
+pub mod oper_states;
+pub mod sock_filter;
+pub mod timestamp_sources;
@@ -0,0 +1,55 @@
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum OperStates {
+    Unknown,
+    NotPresent,
+    Down,
+    LowerLayerDown,
+    Testing,
+    Dormant,
+    Up
+}
+
+impl OperStates {
+
+    pub fn from_code(code: u8) -> Result<Self, String> {
+        for c in [
+            Self::Unknown,
+            Self::NotPresent,
+            Self::Down,
+            Self::LowerLayerDown,
+            Self::Testing,
+            Self::Dormant,
+            Self::Up
+        ] {
+            if c.get_code() == code {
+                return Ok(c);
+            }
+        }
+
+        Err(format!("Couldn't find for code: {}", code))
+    }
+
+    pub fn get_code(&self) -> u8 {
+        match self {
+            Self::Unknown => 0,
+            Self::NotPresent => 1,
+            Self::Down => 2,
+            Self::LowerLayerDown => 3,
+            Self::Testing => 4,
+            Self::Dormant => 5,
+            Self::Up => 6
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::Unknown => "Unknown",
+            Self::NotPresent => "Not Present",
+            Self::Down => "Down",
+            Self::LowerLayerDown => "Lower Layer Down",
+            Self::Testing => "Testing",
+            Self::Dormant => "Dormant",
+            Self::Up => "Up"
+        }.to_string()
+    }
+}
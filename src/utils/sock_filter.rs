@@ -0,0 +1,38 @@
+pub const DEFAULT_SNAPLEN: u32 = 262144;
+
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct SockFilter {
+    code: u16,
+    jt: u8,
+    jf: u8,
+    k: u32
+}
+
+impl SockFilter {
+
+    pub fn new(code: u16, jt: u8, jf: u8, k: u32) -> Self {
+        Self {
+            code,
+            jt,
+            jf,
+            k
+        }
+    }
+
+    pub fn get_code(&self) -> u16 {
+        self.code
+    }
+
+    pub fn get_jt(&self) -> u8 {
+        self.jt
+    }
+
+    pub fn get_jf(&self) -> u8 {
+        self.jf
+    }
+
+    pub fn get_k(&self) -> u32 {
+        self.k
+    }
+}
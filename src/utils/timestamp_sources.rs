@@ -0,0 +1,17 @@
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum TimestampSources {
+    User,
+    Software,
+    Hardware
+}
+
+impl TimestampSources {
+
+    pub fn to_string(&self) -> String {
+        match self {
+            Self::User => "User",
+            Self::Software => "Software",
+            Self::Hardware => "Hardware"
+        }.to_string()
+    }
+}
@@ -2,8 +2,11 @@ use std::{io, mem, ptr};
 use std::net::{IpAddr, Ipv4Addr};
 use std::time::{SystemTime, UNIX_EPOCH};
 use crate::packet::packet::Packet;
+use crate::utils::capture_stats::CaptureStats;
+use crate::utils::sock_filter::SockFilter;
 use crate::utils::data_link_types::DataLinkTypes;
 use crate::windows::devices::Device;
+use crate::inter::capture_source::CaptureSource;
 use crate::windows::sys::{bind, recvfrom, SockAddr, socket, WsaData, WSAIoctl, WSAStartup, AF_INET, IPPROTO_IP, RCVALL_ON, SIO_RCVALL, SOCK_RAW, SockAddrIn, FdSet, TimeVal, select};
 
 #[derive(Debug, Clone)]
@@ -94,9 +97,9 @@ impl Capture {
             let now = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .expect("Time went backwards")
-                .as_millis();
+                .as_nanos();
 
-            return Ok((0, Packet::new(DataLinkTypes::Raw, now, &buffer[..len as usize])));
+            return Ok((0, Packet::new(DataLinkTypes::Raw, now, &buffer[..len as usize])?));
 
         } else if len == -1 {
             return Err(io::Error::last_os_error());
@@ -132,3 +135,35 @@ impl Capture {
         self.device.as_ref()
     }
 }
+
+impl CaptureSource for Capture {
+
+    fn open(&mut self) -> io::Result<()> {
+        Capture::open(self)
+    }
+
+    fn recv(&self) -> io::Result<Packet> {
+        Capture::recv(self).map(|(_, packet)| packet)
+    }
+
+    fn try_recv(&self) -> io::Result<Packet> {
+        Capture::try_recv(self).map(|(_, packet)| packet)
+    }
+
+    fn send(&self, _packet: Packet) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Sending is not supported on this platform yet"))
+    }
+
+    fn stats(&self) -> io::Result<CaptureStats> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Capture stats are not supported on this platform yet"))
+    }
+
+    fn set_filter(&self, _filter: &[SockFilter]) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Filters are not supported on this platform yet"))
+    }
+
+    fn get_data_link_type(&self) -> DataLinkTypes {
+        //RAW SOCKETS ONLY EVER HAND US IP
+        DataLinkTypes::Raw
+    }
+}